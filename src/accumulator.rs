@@ -0,0 +1,282 @@
+use crate::bytes::{newline_prefix, partial_newline_len, search_bytes};
+use crate::line::Line;
+use crate::nlset::NewlineSet;
+use core::iter::FusedIterator;
+
+/// A push-based splitter for breaking text that arrives in chunks into lines
+/// on the UTF-8-encoded newline sequences in a [`NewlineSet`]
+///
+/// Chunks of text are passed to [`push()`][LineAccumulator::push] or
+/// [`push_bytes()`][LineAccumulator::push_bytes], each of which returns an
+/// iterator over the lines completed by that chunk.  Data after the last
+/// complete line is buffered until a later chunk completes it or until
+/// [`finish()`][LineAccumulator::finish] is called to signal the end of the
+/// input.
+///
+/// Newline sequences are never split at chunk boundaries: a multi-byte
+/// sequence like U+0085, U+2028, or U+2029 that is divided between chunks is
+/// still recognized once its remaining bytes arrive, and if the set contains
+/// [`Newline::CrLf`][crate::Newline::CrLf], a CR at the end of a chunk is
+/// held back until the next chunk (or `finish()`) shows whether it is
+/// followed by an LF.
+///
+/// Lines are yielded as [`Line`]s over the bytes of the input, and the
+/// offsets reported by their [`span()`][Line::span] and related methods are
+/// measured from the start of all of the data pushed into the accumulator.
+/// The input need not be valid UTF-8, but if only
+/// [`push()`][LineAccumulator::push] is used, every line is valid UTF-8 and
+/// can be converted to a `Line<'_, str>` with [`Line::to_str()`].
+///
+/// # Example
+///
+/// ```
+/// use newlines::{LineAccumulator, Newline, NewlineSet};
+///
+/// let mut acc = LineAccumulator::new(NewlineSet::UNICODE);
+/// let lines = acc
+///     .push("foo\nbar\r")
+///     .map(|line| (line.content().to_vec(), line.newline()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(lines, [(b"foo".to_vec(), Some(Newline::LineFeed))]);
+///
+/// // The CR is held back until it is known whether an LF follows:
+/// let lines = acc
+///     .push_bytes(b"\nbaz\xE2\x80")
+///     .map(|line| (line.content().to_vec(), line.newline()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(lines, [(b"bar".to_vec(), Some(Newline::CrLf))]);
+///
+/// // Complete the U+2028:
+/// let lines = acc
+///     .push_bytes(b"\xA8quux")
+///     .map(|line| (line.content().to_vec(), line.newline()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(lines, [(b"baz".to_vec(), Some(Newline::LineSeparator))]);
+///
+/// let last = acc.finish().unwrap();
+/// assert_eq!(last.content(), b"quux");
+/// assert_eq!(last.newline(), None);
+/// assert_eq!(last.span(), (15, 19));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineAccumulator {
+    nlset: NewlineSet,
+
+    /// Data that has been pushed but not yet discarded.  This consists of
+    /// the lines most recently yielded, followed by the data for the
+    /// incomplete line.
+    buf: Vec<u8>,
+
+    /// The length of the prefix of `buf` consisting of lines that have
+    /// already been yielded and can be discarded
+    done: usize,
+
+    /// The position in `buf` from which to resume searching for newline
+    /// sequences.  Everything in `buf` from this point onwards is a possible
+    /// prefix of a newline sequence.
+    search_from: usize,
+
+    /// The offset in the input of the start of `buf`
+    offset: usize,
+}
+
+impl LineAccumulator {
+    /// Create a new `LineAccumulator` that splits on the newline sequences in
+    /// `nlset`
+    pub fn new(nlset: NewlineSet) -> LineAccumulator {
+        LineAccumulator {
+            nlset,
+            buf: Vec::new(),
+            done: 0,
+            search_from: 0,
+            offset: 0,
+        }
+    }
+
+    /// Returns the `NewlineSet` that the accumulator splits on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns the buffered data for the current incomplete line
+    pub fn pending(&self) -> &[u8] {
+        &self.buf[self.done..]
+    }
+
+    /// Add a chunk of text to the input, and return an iterator over the
+    /// lines that it completes.  Each line yielded by the iterator ends with
+    /// a newline sequence.
+    ///
+    /// If no bytes have been added with
+    /// [`push_bytes()`][LineAccumulator::push_bytes], the lines are valid
+    /// UTF-8, and [`Line::to_str()`] will succeed on them.
+    ///
+    /// The lines are discarded from the accumulator's buffer on the next call
+    /// to a method of the accumulator, whether or not they have been taken
+    /// from the iterator.
+    pub fn push(&mut self, chunk: &str) -> AccumulatedLines<'_> {
+        self.push_bytes(chunk.as_bytes())
+    }
+
+    /// Add a chunk of bytes to the input, and return an iterator over the
+    /// lines that it completes.  Each line yielded by the iterator ends with
+    /// a newline sequence.
+    ///
+    /// The lines are discarded from the accumulator's buffer on the next call
+    /// to a method of the accumulator, whether or not they have been taken
+    /// from the iterator.
+    pub fn push_bytes(&mut self, chunk: &[u8]) -> AccumulatedLines<'_> {
+        self.discard_done();
+        self.buf.extend_from_slice(chunk);
+        let keep = partial_newline_len(self.nlset, &self.buf[self.search_from..]);
+        let complete = self.buf.len() - keep;
+        let mut pos = self.search_from;
+        while let Some((_, end)) = search_bytes(self.nlset, &self.buf[pos..complete]) {
+            pos += end;
+            self.done = pos;
+        }
+        self.search_from = complete;
+        AccumulatedLines {
+            nlset: self.nlset,
+            text: &self.buf[..self.done],
+            offset: self.offset,
+        }
+    }
+
+    /// Signal the end of the input, and return the final line, if there is
+    /// any buffered data.  The final line ends with a newline sequence only if
+    /// it is a CR that was held back to see whether it was followed by an LF.
+    ///
+    /// After calling this method, the accumulator is empty and can be used to
+    /// split further input, with offsets continuing from the end of the
+    /// previous input.
+    pub fn finish(&mut self) -> Option<Line<'_, [u8]>> {
+        self.discard_done();
+        if self.buf.is_empty() {
+            return None;
+        }
+        self.done = self.buf.len();
+        self.search_from = self.buf.len();
+        let line = match search_bytes(self.nlset, &self.buf) {
+            Some((start, _)) => Line::new(
+                &self.buf[..],
+                self.offset,
+                start,
+                newline_prefix(self.nlset, &self.buf[start..]),
+            ),
+            None => Line::new(&self.buf[..], self.offset, self.buf.len(), None),
+        };
+        Some(line)
+    }
+
+    /// Remove the lines that have already been yielded from `buf`
+    fn discard_done(&mut self) {
+        self.buf.drain(..self.done);
+        self.search_from -= self.done;
+        self.offset += self.done;
+        self.done = 0;
+    }
+}
+
+/// An iterator over the lines completed by a chunk of input passed to a
+/// [`LineAccumulator`]
+///
+/// This struct is created by [`LineAccumulator::push()`] and
+/// [`LineAccumulator::push_bytes()`].  See their documentation for more.
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct AccumulatedLines<'a> {
+    nlset: NewlineSet,
+
+    /// The completed lines that have not been yielded yet
+    text: &'a [u8],
+
+    /// The offset in the input of the start of `text`
+    offset: usize,
+}
+
+impl<'a> Iterator for AccumulatedLines<'a> {
+    type Item = Line<'a, [u8]>;
+
+    fn next(&mut self) -> Option<Line<'a, [u8]>> {
+        let (start, end) = search_bytes(self.nlset, self.text)?;
+        let line = Line::new(
+            &self.text[..end],
+            self.offset,
+            start,
+            newline_prefix(self.nlset, &self.text[start..]),
+        );
+        self.text = &self.text[end..];
+        self.offset += end;
+        Some(line)
+    }
+}
+
+impl FusedIterator for AccumulatedLines<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nl::Newline;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", NewlineSet::UNICODE)]
+    #[case("foo\r\nbar\rbaz\n", NewlineSet::ASCII)]
+    #[case("foo\r\nbar\rbaz\n", Newline::CrLf.into())]
+    #[case("foo\r\nbar\r", NewlineSet::ASCII)]
+    #[case("foo\r\nbar\r", Newline::CrLf.into())]
+    #[case("\r\r\n\n\r\n", NewlineSet::ASCII)]
+    #[case("foo\u{2028}\u{0085}bar\u{2029}baz", NewlineSet::UNICODE)]
+    #[case("foo\u{2028}\u{0085}bar\u{2029}baz", NewlineSet::NLF)]
+    fn chunked(#[case] s: &str, #[case] nlset: NewlineSet) {
+        let expected = nlset
+            .lines_with_newlines(s)
+            .map(|line| (line.as_str().as_bytes(), line.newline(), line.span()))
+            .collect::<Vec<_>>();
+        for chunk_size in 1..=4 {
+            let mut acc = LineAccumulator::new(nlset);
+            let mut lines = Vec::new();
+            for chunk in s.as_bytes().chunks(chunk_size) {
+                for line in acc.push_bytes(chunk) {
+                    assert!(line.newline().is_some());
+                    lines.push((line.as_bytes().to_vec(), line.newline(), line.span()));
+                }
+            }
+            if let Some(line) = acc.finish() {
+                lines.push((line.as_bytes().to_vec(), line.newline(), line.span()));
+            }
+            assert_eq!(
+                lines
+                    .iter()
+                    .map(|(text, nl, span)| (&text[..], *nl, *span))
+                    .collect::<Vec<_>>(),
+                expected,
+                "chunk_size={chunk_size}"
+            );
+            assert_eq!(acc.finish(), None);
+        }
+    }
+
+    #[test]
+    fn reuse_after_finish() {
+        let mut acc = LineAccumulator::new(NewlineSet::ASCII);
+        assert_eq!(acc.push("foo\nbar").count(), 1);
+        assert_eq!(acc.pending(), b"bar");
+        let Some(line) = acc.finish() else {
+            unreachable!("There should be a final line");
+        };
+        assert_eq!(line.span(), (4, 7));
+        assert_eq!(acc.pending(), b"");
+        let lines = acc.push("baz\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].content(), b"baz");
+        assert_eq!(lines[0].span(), (7, 12));
+        let line = lines[0].to_str().unwrap();
+        assert_eq!(line.content(), "baz");
+        assert_eq!(line.newline(), Some(Newline::CrLf));
+        assert_eq!(line.span(), (7, 12));
+    }
+}
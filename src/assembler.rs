@@ -0,0 +1,477 @@
+use crate::bytes::{newline_prefix, partial_newline_len, HeldBytes, Resolved};
+use crate::errors::LineAssemblerError;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+
+/// A fixed-capacity state machine for assembling lines from a stream of bytes
+/// fed in one at a time, splitting on the UTF-8-encoded newline sequences in a
+/// [`NewlineSet`]
+///
+/// A `LineAssembler` stores the content of the current line in an internal
+/// buffer of `N` bytes and does not allocate, making it suitable for
+/// `no_std` environments such as reading from a UART.  Each byte is passed to
+/// [`feed()`][LineAssembler::feed], which returns the content of the line and
+/// the [`Newline`] that terminated it whenever a line is completed.
+///
+/// Bytes that could be the start of a newline sequence — such as the first
+/// byte or two of the UTF-8 encoding of U+2028, or a CR when the set contains
+/// [`Newline::CrLf`] — are held back until the following byte shows whether
+/// they are part of a newline sequence.  As a CR LF pair may be sent with an
+/// arbitrary delay between the CR and the LF, a held-back CR can also be
+/// resolved as a bare CR by calling [`timeout()`][LineAssembler::timeout]
+/// once no further byte has arrived within an application-chosen interval.
+///
+/// When a bare CR is resolved by the next byte, `feed()` returns the line
+/// terminated by the CR, and that byte is processed at the start of the next
+/// call to `feed()` or `timeout()` instead.  In the rare case that the byte is
+/// itself a complete newline sequence (e.g., a form feed), the empty line that
+/// it terminates is returned by that next call.
+///
+/// # Errors
+///
+/// If the content of a line exceeds `N` bytes, [`feed()`][LineAssembler::feed]
+/// returns [`LineAssemblerError::Overflow`], and the rest of the line, up
+/// through its terminating newline sequence, is discarded.  If the content of
+/// a line is not valid UTF-8, the call that completes the line returns
+/// [`LineAssemblerError::InvalidUtf8`], and the line is discarded.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{LineAssembler, Newline, NewlineSet};
+///
+/// let mut asm = LineAssembler::<16>::new(NewlineSet::ASCII);
+/// for &b in b"foo\r" {
+///     assert_eq!(asm.feed(b), Ok(None));
+/// }
+/// // The CR may be followed by an LF:
+/// assert_eq!(asm.feed(b'\n'), Ok(Some(("foo", Newline::CrLf))));
+/// for &b in b"bar\r" {
+///     assert_eq!(asm.feed(b), Ok(None));
+/// }
+/// // No LF arrived in time, so the CR stands on its own:
+/// assert_eq!(asm.timeout(), Ok(Some(("bar", Newline::CarriageReturn))));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineAssembler<const N: usize> {
+    nlset: NewlineSet,
+
+    /// The content of the current line is `buf[..len]`
+    buf: [u8; N],
+    len: usize,
+
+    /// Bytes at the end of the input that may be the start of a newline
+    /// sequence
+    held: HeldBytes,
+
+    /// A byte that was fed in after a bare CR and that has not been processed
+    /// yet
+    deferred: Option<u8>,
+
+    /// Whether `buf[..len]` holds a line that was returned by the previous
+    /// call and should be cleared before processing more input
+    returned: bool,
+
+    /// Whether the current line overflowed the buffer and is being discarded
+    discarding: bool,
+}
+
+/// The result of processing a single byte
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Step {
+    /// The current line has not ended yet
+    Continue,
+
+    /// The current line ended with the given newline sequence
+    Line(Newline),
+
+    /// The current line overflowed the buffer
+    Overflow,
+}
+
+impl<const N: usize> LineAssembler<N> {
+    /// Create a new `LineAssembler` that splits on the newline sequences in
+    /// `nlset`
+    pub fn new(nlset: NewlineSet) -> LineAssembler<N> {
+        LineAssembler {
+            nlset,
+            buf: [0; N],
+            len: 0,
+            held: HeldBytes::default(),
+            deferred: None,
+            returned: false,
+            discarding: false,
+        }
+    }
+
+    /// Returns the `NewlineSet` that the assembler splits on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns the maximum length in bytes of the content of a line
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Discard all buffered data and return the assembler to its initial
+    /// state
+    pub fn reset(&mut self) {
+        *self = LineAssembler::new(self.nlset);
+    }
+
+    /// Process the next byte of input.  If the byte completes a line, the
+    /// content of the line (without its terminating newline sequence) is
+    /// returned along with the [`Newline`] that terminated it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LineAssemblerError::Overflow`] if the current line no longer
+    /// fits in the buffer, or [`LineAssemblerError::InvalidUtf8`] if the
+    /// completed line is not valid UTF-8.
+    pub fn feed(&mut self, byte: u8) -> Result<Option<(&str, Newline)>, LineAssemblerError> {
+        let nl = self.feed_newline(byte)?;
+        Ok(nl.map(|nl| (self.line(), nl)))
+    }
+
+    /// Like [`feed()`][LineAssembler::feed], but only returns the [`Newline`]
+    /// that completed the line; the line itself can then be retrieved with
+    /// [`line()`][LineAssembler::line].  This lets callers that feed bytes in
+    /// a loop return the line without holding a borrow across iterations.
+    pub(crate) fn feed_newline(&mut self, byte: u8) -> Result<Option<Newline>, LineAssemblerError> {
+        self.clear_returned();
+        let step = match self.deferred.take() {
+            Some(d) => match self.process(d) {
+                Step::Continue => self.process(byte),
+                step => {
+                    self.deferred = Some(byte);
+                    step
+                }
+            },
+            None => self.process(byte),
+        };
+        self.finish_step(step)
+    }
+
+    /// Signal that no more input has arrived within some timeout period.  If
+    /// a CR is being held back to see whether it is followed by an LF, it is
+    /// resolved as a bare CR; if this completes a line (i.e., if the
+    /// `NewlineSet` contains [`Newline::CarriageReturn`]), the line is
+    /// returned.  Any other held-back bytes remain buffered.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`feed()`][LineAssembler::feed].
+    pub fn timeout(&mut self) -> Result<Option<(&str, Newline)>, LineAssemblerError> {
+        let nl = self.timeout_newline()?;
+        Ok(nl.map(|nl| (self.line(), nl)))
+    }
+
+    fn timeout_newline(&mut self) -> Result<Option<Newline>, LineAssemblerError> {
+        self.clear_returned();
+        let step = match self.deferred.take() {
+            Some(d) => match self.process(d) {
+                Step::Continue => self.resolve_cr(),
+                step => step,
+            },
+            None => self.resolve_cr(),
+        };
+        self.finish_step(step)
+    }
+
+    /// Signal the end of the input, and return the next remaining line, if
+    /// any.  A held-back CR is resolved as by
+    /// [`timeout()`][LineAssembler::timeout], and any other held-back bytes
+    /// are treated as content.  Each line is returned along with the
+    /// [`Newline`] that terminated it, or with `None` if it was terminated by
+    /// the end of the input.
+    ///
+    /// This method should be called repeatedly until it returns `Ok(None)`,
+    /// after which the assembler is empty and can be used to split further
+    /// input.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`feed()`][LineAssembler::feed].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{LineAssembler, Newline, NewlineSet};
+    ///
+    /// let mut asm = LineAssembler::<16>::new(NewlineSet::ASCII);
+    /// for &b in b"foo\r" {
+    ///     assert_eq!(asm.feed(b), Ok(None));
+    /// }
+    /// assert_eq!(asm.finish(), Ok(Some(("foo", Some(Newline::CarriageReturn)))));
+    /// assert_eq!(asm.finish(), Ok(None));
+    /// for &b in b"bar" {
+    ///     assert_eq!(asm.feed(b), Ok(None));
+    /// }
+    /// assert_eq!(asm.finish(), Ok(Some(("bar", None))));
+    /// assert_eq!(asm.finish(), Ok(None));
+    /// ```
+    pub fn finish(&mut self) -> Result<Option<(&str, Option<Newline>)>, LineAssemblerError> {
+        if let Some(nl) = self.timeout_newline()? {
+            return Ok(Some((self.line(), Some(nl))));
+        }
+        let mut overflowed = false;
+        if let Resolved::Content(held) = self.held.resolve(self.nlset, &[]) {
+            for &b in held.as_slice() {
+                overflowed |= self.push_content(b) == Step::Overflow;
+            }
+        }
+        if self.discarding {
+            self.discarding = false;
+            self.len = 0;
+            return if overflowed {
+                Err(LineAssemblerError::Overflow)
+            } else {
+                Ok(None)
+            };
+        }
+        if self.len == 0 {
+            return Ok(None);
+        }
+        match self.finish_step(Step::Line(Newline::LineFeed))? {
+            Some(_) => Ok(Some((self.line(), None))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the content of the line that was just completed
+    pub(crate) fn line(&self) -> &str {
+        let Ok(s) = core::str::from_utf8(&self.buf[..self.len]) else {
+            unreachable!("finish_step() should have validated the line");
+        };
+        s
+    }
+
+    /// Returns whether the current line overflowed the buffer and the rest of
+    /// it is being discarded
+    #[cfg(feature = "embedded-io")]
+    pub(crate) fn is_discarding(&self) -> bool {
+        self.discarding
+    }
+
+    fn clear_returned(&mut self) {
+        if self.returned {
+            self.returned = false;
+            self.len = 0;
+        }
+    }
+
+    /// Process a single byte of input
+    fn process(&mut self, byte: u8) -> Step {
+        let nlset = self.nlset;
+        match self.held.resolve(nlset, &[byte]) {
+            Resolved::Newline(nl, 0) => {
+                // A bare CR; `byte` is not part of it.
+                self.deferred = Some(byte);
+                return Step::Line(nl);
+            }
+            Resolved::Newline(nl, _) => return Step::Line(nl),
+            Resolved::Pending(_) => return Step::Continue,
+            Resolved::Content(held) => {
+                let mut overflowed = false;
+                for &b in held.as_slice() {
+                    overflowed |= self.push_content(b) == Step::Overflow;
+                }
+                if overflowed {
+                    self.deferred = Some(byte);
+                    return Step::Overflow;
+                }
+            }
+        }
+        if partial_newline_len(nlset, &[byte]) == 1 {
+            self.held.set(&[byte]);
+            Step::Continue
+        } else if let Some(nl) = newline_prefix(nlset, &[byte]) {
+            Step::Line(nl)
+        } else {
+            self.push_content(byte)
+        }
+    }
+
+    /// If a CR is being held back, resolve it as a bare CR
+    fn resolve_cr(&mut self) -> Step {
+        if self.held.as_slice() != b"\r" {
+            return Step::Continue;
+        }
+        match self.held.resolve(self.nlset, &[]) {
+            Resolved::Newline(nl, _) => Step::Line(nl),
+            _ => self.push_content(b'\r'),
+        }
+    }
+
+    /// Append a byte to the content of the current line
+    fn push_content(&mut self, byte: u8) -> Step {
+        if self.discarding {
+            Step::Continue
+        } else if self.len == N {
+            self.discarding = true;
+            self.len = 0;
+            Step::Overflow
+        } else {
+            self.buf[self.len] = byte;
+            self.len += 1;
+            Step::Continue
+        }
+    }
+
+    fn finish_step(&mut self, step: Step) -> Result<Option<Newline>, LineAssemblerError> {
+        match step {
+            Step::Continue => Ok(None),
+            Step::Overflow => Err(LineAssemblerError::Overflow),
+            Step::Line(_) if self.discarding => {
+                self.discarding = false;
+                self.len = 0;
+                Ok(None)
+            }
+            Step::Line(nl) => {
+                self.returned = true;
+                match core::str::from_utf8(&self.buf[..self.len]) {
+                    Ok(_) => Ok(Some(nl)),
+                    Err(e) => Err(LineAssemblerError::InvalidUtf8(e)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Feed `data` to a `LineAssembler` with a capacity of 8 bytes, calling
+    /// `timeout()` at the end, and collect the results
+    fn assemble(
+        data: &[u8],
+        nlset: NewlineSet,
+    ) -> Vec<Result<(String, Newline), LineAssemblerError>> {
+        let mut asm = LineAssembler::<8>::new(nlset);
+        let mut results = Vec::new();
+        let mut push = |r: Result<Option<(&str, Newline)>, LineAssemblerError>| match r {
+            Ok(Some((s, nl))) => results.push(Ok((String::from(s), nl))),
+            Ok(None) => (),
+            Err(e) => results.push(Err(e)),
+        };
+        for &b in data {
+            push(asm.feed(b));
+        }
+        push(asm.timeout());
+        push(asm.timeout());
+        results
+    }
+
+    #[rstest]
+    #[case(b"", NewlineSet::ASCII, vec![])]
+    #[case(b"foo\r\nbar\rbaz\n", NewlineSet::ASCII, vec![("foo", Newline::CrLf), ("bar", Newline::CarriageReturn), ("baz", Newline::LineFeed)])]
+    #[case(b"foo\r\r\n\r", NewlineSet::ASCII, vec![("foo", Newline::CarriageReturn), ("", Newline::CrLf), ("", Newline::CarriageReturn)])]
+    #[case(b"a\r\x0Cb\n", NewlineSet::UNICODE, vec![("a", Newline::CarriageReturn), ("", Newline::FormFeed), ("b", Newline::LineFeed)])]
+    #[case(b"a\r\x0C", NewlineSet::UNICODE, vec![("a", Newline::CarriageReturn), ("", Newline::FormFeed)])]
+    #[case(b"a\rb\r\n", Newline::CrLf.into(), vec![("a\rb", Newline::CrLf)])]
+    #[case(b"a\xC2\x85\xC3\xA9\xE2\x80\xA8", NewlineSet::UNICODE, vec![("a", Newline::NextLine), ("\u{e9}", Newline::LineSeparator)])]
+    #[case(b"\xE2\x80\xA9\xE2\x82\xAC\n", NewlineSet::UNICODE, vec![("", Newline::ParagraphSeparator), ("\u{20ac}", Newline::LineFeed)])]
+    #[case(b"\xE2\x80\xA8\n", NewlineSet::ASCII, vec![("\u{2028}", Newline::LineFeed)])]
+    fn lines(
+        #[case] data: &[u8],
+        #[case] nlset: NewlineSet,
+        #[case] expected: Vec<(&str, Newline)>,
+    ) {
+        let expected = expected
+            .into_iter()
+            .map(|(s, nl)| Ok((String::from(s), nl)))
+            .collect::<Vec<_>>();
+        assert_eq!(assemble(data, nlset), expected);
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(
+            assemble(b"12345678\n123456789\nabc\n", NewlineSet::ASCII),
+            [
+                Ok((String::from("12345678"), Newline::LineFeed)),
+                Err(LineAssemblerError::Overflow),
+                Ok((String::from("abc"), Newline::LineFeed)),
+            ]
+        );
+    }
+
+    #[test]
+    fn newline_at_capacity() {
+        assert_eq!(
+            assemble(b"12345678\xE2\x80\xA8x\n", NewlineSet::UNICODE),
+            [
+                Ok((String::from("12345678"), Newline::LineSeparator)),
+                Ok((String::from("x"), Newline::LineFeed)),
+            ]
+        );
+        assert_eq!(
+            assemble(b"12345678\xC2\xA9\xC2\x85ok\n", NewlineSet::UNICODE),
+            [
+                Err(LineAssemblerError::Overflow),
+                Ok((String::from("ok"), Newline::LineFeed)),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(b"", NewlineSet::ASCII, vec![])]
+    #[case(b"foo\nbar", NewlineSet::ASCII, vec![("foo", Some(Newline::LineFeed)), ("bar", None)])]
+    #[case(b"foo\r", NewlineSet::ASCII, vec![("foo", Some(Newline::CarriageReturn))])]
+    #[case(b"foo\r", Newline::CrLf.into(), vec![("foo\r", None)])]
+    #[case(b"a\r\x0C", NewlineSet::UNICODE, vec![("a", Some(Newline::CarriageReturn)), ("", Some(Newline::FormFeed))])]
+    #[case(b"a\rb", NewlineSet::ASCII, vec![("a", Some(Newline::CarriageReturn)), ("b", None)])]
+    #[case(b"\xC3\xA9\xC2", NewlineSet::ASCII, vec![])]
+    fn finish(
+        #[case] data: &[u8],
+        #[case] nlset: NewlineSet,
+        #[case] expected: Vec<(&str, Option<Newline>)>,
+    ) {
+        let mut asm = LineAssembler::<8>::new(nlset);
+        let mut lines = Vec::new();
+        for &b in data {
+            if let Ok(Some((s, nl))) = asm.feed(b) {
+                lines.push((String::from(s), Some(nl)));
+            }
+        }
+        let mut errors = 0;
+        loop {
+            match asm.finish() {
+                Ok(Some((s, nl))) => lines.push((String::from(s), nl)),
+                Ok(None) => break,
+                Err(_) => errors += 1,
+            }
+        }
+        let expected = expected
+            .into_iter()
+            .map(|(s, nl)| (String::from(s), nl))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, expected);
+        assert_eq!(errors, usize::from(data.ends_with(b"\xC2")));
+        assert_eq!(asm.feed(b'x'), Ok(None));
+        assert_eq!(asm.finish(), Ok(Some(("x", None))));
+    }
+
+    #[test]
+    fn finish_overflow() {
+        let mut asm = LineAssembler::<2>::new(NewlineSet::UNICODE);
+        for &b in b"ab\xC2" {
+            assert_eq!(asm.feed(b), Ok(None));
+        }
+        assert_eq!(asm.finish(), Err(LineAssemblerError::Overflow));
+        assert_eq!(asm.finish(), Ok(None));
+        assert_eq!(asm.feed(b'x'), Ok(None));
+        assert_eq!(asm.feed(b'\n'), Ok(Some(("x", Newline::LineFeed))));
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let r = assemble(b"a\xFFb\nc\n", NewlineSet::ASCII);
+        assert_eq!(r.len(), 2);
+        assert!(matches!(r[0], Err(LineAssemblerError::InvalidUtf8(_))));
+        assert_eq!(r[1], Ok((String::from("c"), Newline::LineFeed)));
+    }
+}
@@ -0,0 +1,313 @@
+/// Generate an extension trait for reading lines from an `AsyncBufRead`
+/// trait, along with its future & stream types.
+///
+/// `futures-io` and `tokio` each define their own `AsyncBufRead` trait with
+/// the same shape, and a single type can implement both, so the two
+/// implementations cannot share blanket impls.  Instead, this macro is
+/// invoked once per runtime with the path to the runtime's trait, the name of
+/// the Cargo feature that enables it, the names to give the generated items,
+/// and the runtime's `BufReader` type (used in the generated tests);
+/// everything else is shared.
+macro_rules! async_bufread_ext {
+    (
+        feature: $feature:literal,
+        reader: $reader:literal,
+        trait: $AsyncBufRead:path,
+        ext: $Ext:ident,
+        read_line_with: $ReadLineWith:ident,
+        lines: $Lines:ident,
+        buf_reader: $BufReader:path,
+    ) => {
+        use core::future::Future;
+        use core::pin::Pin;
+        use core::task::{ready, Context, Poll};
+        use futures_core::Stream;
+        use std::io;
+        use $crate::bufread::{finish_line, finish_read_line, LineScanner, Scan};
+        use $crate::nl::Newline;
+        use $crate::nlset::NewlineSet;
+
+        /// Extension trait for reading lines from a
+        #[doc = concat!("[`", $reader, "`]")]
+        /// value, split on the UTF-8-encoded newline sequences in a
+        /// [`NewlineSet`]
+        ///
+        /// This is the asynchronous counterpart of
+        /// [`BufReadNewlineExt`][crate::BufReadNewlineExt], and it provides
+        /// the same guarantees: newline sequences that are split across
+        /// multiple fills of the reader's buffer are still recognized as a
+        /// single unit, and a CR at the end of a buffer fill that could be the
+        /// start of a CR LF pair is held back until the next byte has been
+        /// read.
+        ///
+        /// This trait is implemented for all types that implement
+        #[doc = concat!("`", $reader, "`.")]
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use futures_util::TryStreamExt;
+        #[doc = concat!("use newlines::{", stringify!($Ext), ", Newline, NewlineSet};")]
+        ///
+        /// # fn main() -> std::io::Result<()> {
+        /// # futures_executor::block_on(async {
+        /// let mut reader = "foo\r\nbar\u{2028}baz".as_bytes();
+        /// let mut line = String::new();
+        /// assert_eq!(
+        ///     reader.read_line_with(&mut line, NewlineSet::UNICODE).await?,
+        ///     Some(Newline::CrLf)
+        /// );
+        /// assert_eq!(line, "foo\r\n");
+        /// let lines = reader
+        ///     .lines_with(NewlineSet::UNICODE)
+        ///     .try_collect::<Vec<_>>()
+        ///     .await?;
+        /// assert_eq!(
+        ///     lines,
+        ///     [
+        ///         (String::from("bar"), Some(Newline::LineSeparator)),
+        ///         (String::from("baz"), None),
+        ///     ]
+        /// );
+        /// # Ok(())
+        /// # })
+        /// # }
+        /// ```
+        #[cfg_attr(docsrs, doc(cfg(feature = $feature)))]
+        pub trait $Ext: $AsyncBufRead {
+            /// Read bytes from the reader until a newline sequence in `nlset`
+            /// or end of file is reached, and append them (including the
+            /// newline sequence, if any) to `buf`.
+            ///
+            /// The returned future resolves to the [`Newline`] that
+            /// terminated the line, or to `None` if end of file was reached
+            /// first.  If `None` is returned and nothing was appended to
+            /// `buf`, the reader was already at end of file.
+            ///
+            /// If the data read is not valid UTF-8, the future resolves to an
+            /// error of kind [`io::ErrorKind::InvalidData`], and `buf` is left
+            /// unmodified (though the data is still consumed from the
+            /// reader).
+            ///
+            /// This future is not cancellation safe: if it is dropped before
+            /// it completes, any data that it has read is lost, and the
+            /// contents of `buf` are cleared.
+            fn read_line_with<'a>(
+                &'a mut self,
+                buf: &'a mut String,
+                nlset: NewlineSet,
+            ) -> $ReadLineWith<'a, Self>
+            where
+                Self: Unpin;
+
+            /// Returns a [`Stream`] over the lines of the reader, split on the
+            /// newline sequences in `nlset`.
+            ///
+            /// Each line is yielded as an `io::Result` of the line's content
+            /// (without its terminating newline sequence) paired with the
+            /// [`Newline`] that terminated it, or with `None` if the line was
+            /// terminated by end of file.  A newline at the end of the input
+            /// does not produce a trailing empty line.
+            fn lines_with(self, nlset: NewlineSet) -> $Lines<Self>
+            where
+                Self: Sized;
+        }
+
+        impl<R: $AsyncBufRead + ?Sized> $Ext for R {
+            fn read_line_with<'a>(
+                &'a mut self,
+                buf: &'a mut String,
+                nlset: NewlineSet,
+            ) -> $ReadLineWith<'a, Self>
+            where
+                Self: Unpin,
+            {
+                let bytes = core::mem::take(buf).into_bytes();
+                let scanner = LineScanner::new(nlset);
+                $ReadLineWith {
+                    reader: self,
+                    buf,
+                    old_len: bytes.len(),
+                    bytes,
+                    scanner,
+                }
+            }
+
+            fn lines_with(self, nlset: NewlineSet) -> $Lines<Self>
+            where
+                Self: Sized,
+            {
+                $Lines {
+                    reader: self,
+                    nlset,
+                    buf: Vec::new(),
+                    scanner: LineScanner::new(nlset),
+                }
+            }
+        }
+
+        /// Poll `reader` for bytes until a newline sequence or end of file is
+        /// reached, appending them to `buf`
+        fn poll_read_line<R: $AsyncBufRead + ?Sized>(
+            mut reader: Pin<&mut R>,
+            cx: &mut Context<'_>,
+            buf: &mut Vec<u8>,
+            scanner: &mut LineScanner,
+        ) -> Poll<io::Result<Option<Newline>>> {
+            loop {
+                let avail = ready!(reader.as_mut().poll_fill_buf(cx))?;
+                let (consumed, scan) = scanner.scan(buf, avail);
+                reader.as_mut().consume(consumed);
+                if let Scan::Done(nl) = scan {
+                    return Poll::Ready(Ok(nl));
+                }
+            }
+        }
+
+        #[doc = concat!("Future for the [`", stringify!($Ext), "::read_line_with()`] method")]
+        #[cfg_attr(docsrs, doc(cfg(feature = $feature)))]
+        #[derive(Debug)]
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct $ReadLineWith<'a, R: ?Sized> {
+            reader: &'a mut R,
+            buf: &'a mut String,
+
+            /// The contents of `buf` followed by the bytes read so far
+            bytes: Vec<u8>,
+
+            /// The length of `buf` before reading
+            old_len: usize,
+
+            scanner: LineScanner,
+        }
+
+        impl<R: $AsyncBufRead + ?Sized + Unpin> Future for $ReadLineWith<'_, R> {
+            type Output = io::Result<Option<Newline>>;
+
+            fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let this = &mut *self;
+                let r = ready!(poll_read_line(
+                    Pin::new(&mut *this.reader),
+                    cx,
+                    &mut this.bytes,
+                    &mut this.scanner
+                ));
+                let bytes = core::mem::take(&mut this.bytes);
+                Poll::Ready(finish_read_line(this.buf, bytes, this.old_len, r))
+            }
+        }
+
+        /// A [`Stream`] over the lines of a
+        #[doc = concat!("[`", $reader, "`]")]
+        /// value, split on the newline sequences in a [`NewlineSet`]
+        ///
+        /// This struct is created by
+        #[doc = concat!("[`", stringify!($Ext), "::lines_with()`].")]
+        /// See its documentation for more.
+        #[cfg_attr(docsrs, doc(cfg(feature = $feature)))]
+        #[derive(Debug)]
+        #[must_use = "streams do nothing unless polled"]
+        pub struct $Lines<R> {
+            reader: R,
+            nlset: NewlineSet,
+
+            /// The bytes of the current line read so far
+            buf: Vec<u8>,
+
+            scanner: LineScanner,
+        }
+
+        impl<R> $Lines<R> {
+            /// Return the inner reader.
+            ///
+            /// Any data that has been read as part of an incomplete line is
+            /// lost.
+            pub fn into_inner(self) -> R {
+                self.reader
+            }
+        }
+
+        impl<R: $AsyncBufRead + Unpin> Stream for $Lines<R> {
+            type Item = io::Result<(String, Option<Newline>)>;
+
+            fn poll_next(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Self::Item>> {
+                let this = &mut *self;
+                let r = ready!(poll_read_line(
+                    Pin::new(&mut this.reader),
+                    cx,
+                    &mut this.buf,
+                    &mut this.scanner
+                ));
+                let bytes = core::mem::take(&mut this.buf);
+                this.scanner = LineScanner::new(this.nlset);
+                Poll::Ready(finish_line(bytes, r))
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use futures_executor::block_on;
+            use futures_util::TryStreamExt;
+            use rstest::rstest;
+            use $BufReader;
+
+            #[rstest]
+            #[case("", NewlineSet::UNICODE)]
+            #[case("foo\r\nbar\rbaz\n", NewlineSet::ASCII)]
+            #[case("foo\r\nbar\rbaz\n", Newline::CrLf.into())]
+            #[case("\r\r\n\n\r", NewlineSet::ASCII)]
+            #[case("foo\u{2028}\u{0085}bar\u{2029}\r", NewlineSet::UNICODE)]
+            fn read_lines(#[case] s: &str, #[case] nlset: NewlineSet) {
+                let expected = nlset.lines_with_newlines(s).collect::<Vec<_>>();
+                for capacity in 1..=4 {
+                    let mut reader = BufReader::with_capacity(capacity, s.as_bytes());
+                    for line in &expected {
+                        let mut buf = String::from("prefix");
+                        assert_eq!(
+                            block_on(reader.read_line_with(&mut buf, nlset)).unwrap(),
+                            line.newline(),
+                            "capacity={capacity}"
+                        );
+                        assert_eq!(buf, format!("prefix{}", line.as_str()));
+                    }
+                    let mut buf = String::new();
+                    assert_eq!(
+                        block_on(reader.read_line_with(&mut buf, nlset)).unwrap(),
+                        None
+                    );
+                    assert_eq!(buf, "");
+                    let reader = BufReader::with_capacity(capacity, s.as_bytes());
+                    assert_eq!(
+                        block_on(reader.lines_with(nlset).try_collect::<Vec<_>>()).unwrap(),
+                        expected
+                            .iter()
+                            .map(|line| (String::from(line.content()), line.newline()))
+                            .collect::<Vec<_>>(),
+                        "capacity={capacity}"
+                    );
+                }
+            }
+
+            #[test]
+            fn invalid_utf8() {
+                let mut reader = &b"foo\xFF\nbar\n"[..];
+                let mut buf = String::from("prefix");
+                let e = block_on(reader.read_line_with(&mut buf, NewlineSet::ASCII)).unwrap_err();
+                assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+                assert_eq!(buf, "prefix");
+                let mut lines = reader.lines_with(NewlineSet::ASCII);
+                assert_eq!(
+                    block_on(lines.try_next()).unwrap(),
+                    Some((String::from("bar"), Some(Newline::LineFeed)))
+                );
+                assert_eq!(block_on(lines.try_next()).unwrap(), None);
+            }
+        }
+    };
+}
+
+pub(crate) use async_bufread_ext;
@@ -0,0 +1,277 @@
+use crate::haystack::{private::HaystackImpl, Haystack};
+use crate::iter::{Lines, LinesInclusive, LinesWithNewlines};
+use crate::line::Line;
+use crate::nl::Newline;
+use crate::pattern::{rsplit_once, split_once, NewlinePattern};
+use ::bstr::BStr;
+#[cfg(feature = "std")]
+use {
+    crate::nlset::NewlineSet,
+    crate::normalize::{normalize_bytes, normalize_bytes_in_place},
+    ::bstr::BString,
+    std::borrow::Cow,
+};
+
+mod private {
+    #[allow(unnameable_types)]
+    pub trait Sealed {}
+
+    impl Sealed for bstr::BStr {}
+
+    #[cfg(feature = "std")]
+    impl Sealed for bstr::BString {}
+}
+
+impl Haystack for BStr {}
+
+impl HaystackImpl for BStr {
+    fn haystack_len(&self) -> usize {
+        self.len()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &BStr {
+        &self[start..end]
+    }
+
+    fn search_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        <[u8]>::search_pattern(self, pattern)
+    }
+
+    fn rsearch_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        <[u8]>::rsearch_pattern(self, pattern)
+    }
+
+    fn matched_newline(&self, start: usize, end: usize) -> Newline {
+        <[u8]>::matched_newline(self, start, end)
+    }
+
+    fn newline_window_end(&self) -> &BStr {
+        BStr::new(<[u8]>::newline_window_end(self))
+    }
+
+    fn newline_window_at(&self, idx: usize) -> Option<&BStr> {
+        <[u8]>::newline_window_at(self, idx).map(BStr::new)
+    }
+}
+
+impl<'a> Line<'a, BStr> {
+    /// Returns the content of the line followed by its terminating newline
+    /// sequence (if any)
+    #[cfg_attr(docsrs, doc(cfg(feature = "bstr")))]
+    pub fn as_bstr(&self) -> &'a BStr {
+        self.text()
+    }
+}
+
+/// Extension trait for splitting & normalizing [`BStr`] byte strings on the
+/// UTF-8 encodings of the newline sequences matched by a [`NewlinePattern`]
+///
+/// This is the [`bstr`] counterpart of
+/// [`NewlineBytesExt`][crate::NewlineBytesExt]; the methods here return
+/// `&BStr` slices instead of `&[u8]`.  Because `BString` dereferences to
+/// `BStr`, these methods are also available on `BString` values.
+///
+/// # Example
+///
+/// ```
+/// use bstr::{BStr, ByteSlice};
+/// use newlines::{Newline, NewlineBStrExt, NewlineSet};
+///
+/// let s = b"foo\r\nbar\xE2\x80\xA8baz".as_bstr();
+/// assert_eq!(
+///     s.lines_with(NewlineSet::UNICODE).collect::<Vec<_>>(),
+///     [BStr::new("foo"), BStr::new("bar"), BStr::new("baz")],
+/// );
+/// assert_eq!(
+///     s.split_once_newline(NewlineSet::UNICODE),
+///     Some((BStr::new("foo"), Newline::CrLf, BStr::new(b"bar\xE2\x80\xA8baz"))),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "bstr")))]
+pub trait NewlineBStrExt: private::Sealed {
+    /// Returns an iterator over the lines of the byte string, split on the
+    /// newline sequences matched by `pattern`.  The lines do not include their
+    /// terminating newline sequences, and a newline at the end of the byte
+    /// string does not produce a trailing empty line.
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, BStr>;
+
+    /// Returns an iterator over the lines of the byte string, split on the
+    /// newline sequences matched by `pattern`, with each line yielded as a
+    /// [`Line`] that records the [`Newline`] that terminated it and the byte
+    /// offsets of the line's parts.
+    fn lines_with_newlines<P: NewlinePattern>(&self, pattern: P) -> LinesWithNewlines<'_, P, BStr>;
+
+    /// Returns an iterator over the lines of the byte string, split on the
+    /// newline sequences matched by `pattern`.  The lines include their
+    /// terminating newline sequences, and a newline at the end of the byte
+    /// string does not produce a trailing empty line.
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, BStr>;
+
+    /// Splits the byte string on the first newline sequence matched by
+    /// `pattern`, returning the part before the newline, the [`Newline`]
+    /// itself, and the part after the newline
+    fn split_once_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(&BStr, Newline, &BStr)>;
+
+    /// Splits the byte string on the last newline sequence matched by
+    /// `pattern`, returning the part before the newline, the [`Newline`]
+    /// itself, and the part after the newline
+    fn rsplit_once_newline<P: NewlinePattern>(&self, pattern: P)
+        -> Option<(&BStr, Newline, &BStr)>;
+
+    /// Replace every newline sequence in the byte string that is in the
+    /// [`NewlineSet`] `from` with the newline sequence `to`.
+    ///
+    /// This is the same as [`normalize_bytes()`][crate::normalize_bytes],
+    /// except that the result is a `BStr`.  If the byte string does not
+    /// contain any newline sequences that need to be replaced, it is returned
+    /// as-is without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bstr::ByteSlice;
+    /// use newlines::{Newline, NewlineBStrExt, NewlineSet};
+    ///
+    /// let s = b"foo\r\nbar\r".as_bstr();
+    /// assert_eq!(
+    ///     s.normalize_newlines(NewlineSet::ASCII, Newline::LineFeed),
+    ///     b"foo\nbar\n".as_bstr(),
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "bstr", feature = "std"))))]
+    fn normalize_newlines(&self, from: NewlineSet, to: Newline) -> Cow<'_, BStr>;
+}
+
+impl NewlineBStrExt for BStr {
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, BStr> {
+        Lines::new(pattern, self)
+    }
+
+    fn lines_with_newlines<P: NewlinePattern>(&self, pattern: P) -> LinesWithNewlines<'_, P, BStr> {
+        LinesWithNewlines::new(pattern, self)
+    }
+
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, BStr> {
+        LinesInclusive::new(pattern, self)
+    }
+
+    fn split_once_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(&BStr, Newline, &BStr)> {
+        split_once(&pattern, self)
+    }
+
+    fn rsplit_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&BStr, Newline, &BStr)> {
+        rsplit_once(&pattern, self)
+    }
+
+    #[cfg(feature = "std")]
+    fn normalize_newlines(&self, from: NewlineSet, to: Newline) -> Cow<'_, BStr> {
+        match normalize_bytes(self, from, to) {
+            Cow::Borrowed(s) => Cow::Borrowed(BStr::new(s)),
+            Cow::Owned(buf) => Cow::Owned(BString::from(buf)),
+        }
+    }
+}
+
+/// Extension trait for normalizing the newline sequences in a [`BString`] in
+/// place
+///
+/// # Example
+///
+/// ```
+/// use bstr::BString;
+/// use newlines::{Newline, NewlineBStringExt, NewlineSet};
+///
+/// let mut s = BString::from("foo\nbar\u{2028}");
+/// s.normalize_newlines_in_place(NewlineSet::UNICODE, Newline::CrLf);
+/// assert_eq!(s, "foo\r\nbar\r\n");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "bstr", feature = "std"))))]
+pub trait NewlineBStringExt: private::Sealed {
+    /// Replace every newline sequence in the byte string that is in the
+    /// [`NewlineSet`] `from` with the newline sequence `to`, modifying the
+    /// byte string in place.
+    ///
+    /// This is the same as
+    /// [`normalize_bytes_in_place()`][crate::normalize_bytes_in_place].
+    fn normalize_newlines_in_place(&mut self, from: NewlineSet, to: Newline);
+}
+
+#[cfg(feature = "std")]
+impl NewlineBStringExt for BString {
+    fn normalize_newlines_in_place(&mut self, from: NewlineSet, to: Newline) {
+        normalize_bytes_in_place(self, from, to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlset::NewlineSet;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(b"", vec![])]
+    #[case(b"foo\r\nbar\n", vec![(&b"foo"[..], Some(Newline::CrLf)), (b"bar", Some(Newline::LineFeed))])]
+    #[case(b"\xFF\r\xC2\x85\xE2\x80", vec![(&b"\xFF"[..], Some(Newline::CarriageReturn)), (b"", Some(Newline::NextLine)), (b"\xE2\x80", None)])]
+    fn lines_with_newlines(#[case] s: &[u8], #[case] lines: Vec<(&[u8], Option<Newline>)>) {
+        let s = BStr::new(s);
+        let lines = lines
+            .into_iter()
+            .map(|(content, nl)| (BStr::new(content), nl))
+            .collect_vec();
+        assert_eq!(
+            s.lines_with_newlines(NewlineSet::UNICODE)
+                .map(|line| (line.content(), line.newline()))
+                .collect_vec(),
+            lines
+        );
+        assert_eq!(
+            s.lines_with(NewlineSet::UNICODE).rev().collect_vec(),
+            lines
+                .iter()
+                .rev()
+                .map(|&(content, _)| content)
+                .collect_vec()
+        );
+        assert_eq!(
+            s.split_lines_inclusive(NewlineSet::UNICODE)
+                .map(|line| line.len())
+                .sum::<usize>(),
+            s.len()
+        );
+    }
+
+    #[test]
+    fn rsplit_once_newline() {
+        let s = BStr::new("foo\r\nbar\r\n");
+        assert_eq!(
+            s.rsplit_once_newline(NewlineSet::ASCII),
+            Some((BStr::new("foo\r\nbar"), Newline::CrLf, BStr::new("")))
+        );
+        assert_eq!(
+            s.rsplit_once_newline(Newline::LineFeed),
+            Some((BStr::new("foo\r\nbar\r"), Newline::LineFeed, BStr::new("")))
+        );
+        assert_eq!(s.rsplit_once_newline(NewlineSet::EMPTY), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn normalize_newlines() {
+        let s = BStr::new("foo\nbar\n");
+        assert!(matches!(
+            s.normalize_newlines(NewlineSet::ASCII, Newline::LineFeed),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            s.normalize_newlines(NewlineSet::ASCII, Newline::CrLf),
+            BStr::new("foo\r\nbar\r\n")
+        );
+    }
+}
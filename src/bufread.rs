@@ -0,0 +1,347 @@
+use crate::bytes::{newline_prefix, partial_newline_len, search_bytes, HeldBytes, Resolved};
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use std::io::{self, BufRead};
+
+/// Extension trait for reading lines from a [`BufRead`] value, split on the
+/// UTF-8-encoded newline sequences in a [`NewlineSet`]
+///
+/// Unlike [`BufRead::read_line()`] and [`BufRead::lines()`], which only
+/// recognize LF and CR LF, the methods of this trait split lines on whichever
+/// newline sequences are in the given set.  Newline sequences that are split
+/// across multiple fills of the reader's buffer are still recognized as a
+/// single unit; in particular, if a CR at the end of a buffer fill could be
+/// the start of a CR LF pair, it is held back until the next byte has been
+/// read.
+///
+/// This trait is implemented for all types that implement `BufRead`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{BufReadNewlineExt, Newline, NewlineSet};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut reader = "foo\r\nbar\u{2028}baz".as_bytes();
+/// let mut line = String::new();
+/// assert_eq!(
+///     reader.read_line_with(&mut line, NewlineSet::UNICODE)?,
+///     Some(Newline::CrLf)
+/// );
+/// assert_eq!(line, "foo\r\n");
+/// let lines = reader
+///     .lines_with(NewlineSet::UNICODE)
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(lines, ["bar", "baz"]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub trait BufReadNewlineExt: BufRead {
+    /// Read bytes from the reader until a newline sequence in `nlset` or end
+    /// of file is reached, and append them (including the newline sequence,
+    /// if any) to `buf`.
+    ///
+    /// Returns the [`Newline`] that terminated the line, or `None` if end of
+    /// file was reached first.  If `None` is returned and nothing was appended
+    /// to `buf`, the reader was already at end of file.
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`BufRead::read_line()`].  In
+    /// particular, if the data read is not valid UTF-8, an error of kind
+    /// [`io::ErrorKind::InvalidData`] is returned, and `buf` is left
+    /// unmodified (though the data is still consumed from the reader).
+    fn read_line_with(
+        &mut self,
+        buf: &mut String,
+        nlset: NewlineSet,
+    ) -> io::Result<Option<Newline>>;
+
+    /// Returns an iterator over the lines of the reader, split on the newline
+    /// sequences in `nlset`.
+    ///
+    /// Each line is yielded as an `io::Result<String>` without its
+    /// terminating newline sequence.  A newline at the end of the input does
+    /// not produce a trailing empty line.
+    fn lines_with(self, nlset: NewlineSet) -> BufReadLines<Self>
+    where
+        Self: Sized;
+}
+
+impl<R: BufRead + ?Sized> BufReadNewlineExt for R {
+    fn read_line_with(
+        &mut self,
+        buf: &mut String,
+        nlset: NewlineSet,
+    ) -> io::Result<Option<Newline>> {
+        let mut bytes = core::mem::take(buf).into_bytes();
+        let old_len = bytes.len();
+        let r = read_until_newline(self, &mut bytes, nlset);
+        finish_read_line(buf, bytes, old_len, r)
+    }
+
+    fn lines_with(self, nlset: NewlineSet) -> BufReadLines<Self>
+    where
+        Self: Sized,
+    {
+        BufReadLines {
+            reader: self,
+            nlset,
+        }
+    }
+}
+
+/// Read bytes from `reader` until a newline sequence in `nlset` or end of file
+/// is reached, appending them to `buf`
+fn read_until_newline<R: BufRead + ?Sized>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    nlset: NewlineSet,
+) -> io::Result<Option<Newline>> {
+    let mut scanner = LineScanner::new(nlset);
+    loop {
+        let avail = match reader.fill_buf() {
+            Ok(avail) => avail,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let (consumed, scan) = scanner.scan(buf, avail);
+        reader.consume(consumed);
+        if let Scan::Done(nl) = scan {
+            return Ok(nl);
+        }
+    }
+}
+
+/// Store the bytes read by a `read_line_with()` call into `buf`, which held
+/// the first `old_len` bytes of `bytes` before the call.  If the bytes read
+/// are not valid UTF-8, `buf` is restored to its original contents and an
+/// error is returned.
+pub(crate) fn finish_read_line(
+    buf: &mut String,
+    bytes: Vec<u8>,
+    old_len: usize,
+    r: io::Result<Option<Newline>>,
+) -> io::Result<Option<Newline>> {
+    match String::from_utf8(bytes) {
+        Ok(s) => {
+            *buf = s;
+            r
+        }
+        Err(e) => {
+            let mut bytes = e.into_bytes();
+            bytes.truncate(old_len);
+            let Ok(s) = String::from_utf8(bytes) else {
+                unreachable!("`buf` should have been valid UTF-8 before reading");
+            };
+            *buf = s;
+            r?;
+            Err(invalid_utf8())
+        }
+    }
+}
+
+/// Convert the bytes of a line read by a [`LineScanner`] and the result of
+/// reading it into an item for a stream of lines.  Returns `None` if the
+/// reader was already at end of file.
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+pub(crate) fn finish_line(
+    mut bytes: Vec<u8>,
+    r: io::Result<Option<Newline>>,
+) -> Option<io::Result<(String, Option<Newline>)>> {
+    match r {
+        Ok(None) if bytes.is_empty() => None,
+        Ok(nl) => {
+            bytes.truncate(bytes.len() - nl.map_or(0, |nl| nl.len_utf8()));
+            Some(
+                String::from_utf8(bytes)
+                    .map(|s| (s, nl))
+                    .map_err(|_| invalid_utf8()),
+            )
+        }
+        Err(e) => Some(Err(e)),
+    }
+}
+
+/// Returns the error reported when a line read from a reader is not valid
+/// UTF-8
+pub(crate) fn invalid_utf8() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
+
+/// State for reading a line from a buffered reader one fill of the reader's
+/// buffer at a time
+#[derive(Clone, Debug)]
+pub(crate) struct LineScanner {
+    nlset: NewlineSet,
+
+    /// Bytes at the end of the line buffer that may be the start of a
+    /// newline sequence
+    held: HeldBytes,
+}
+
+/// The outcome of passing a fill of a reader's buffer to
+/// [`LineScanner::scan()`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Scan {
+    /// The end of the line has not been reached yet
+    Pending,
+
+    /// The end of the line was reached.  The field is the newline sequence
+    /// that terminated the line, or `None` if the line was terminated by end
+    /// of file.
+    Done(Option<Newline>),
+}
+
+impl LineScanner {
+    /// Create a new `LineScanner` for reading a line
+    pub(crate) fn new(nlset: NewlineSet) -> LineScanner {
+        LineScanner {
+            nlset,
+            held: HeldBytes::default(),
+        }
+    }
+
+    /// Append the data in `avail` (the contents of a reader's buffer, which
+    /// is empty at end of file) up through the end of the current line to
+    /// `buf`.  Returns the number of bytes of `avail` that the caller must
+    /// consume from the reader, along with whether the end of the line was
+    /// reached.
+    ///
+    /// If `avail` ends with bytes that could be the start of a newline
+    /// sequence — such as a CR that could be followed by an LF — those bytes
+    /// are consumed and appended, but they are only matched once the next
+    /// fill (or end of file) shows how the sequence continues.
+    pub(crate) fn scan(&mut self, buf: &mut Vec<u8>, avail: &[u8]) -> (usize, Scan) {
+        let nlset = self.nlset;
+        // Held bytes have already been appended to `buf`.
+        match self.held.resolve(nlset, avail) {
+            Resolved::Newline(nl, consumed) => {
+                buf.extend_from_slice(&avail[..consumed]);
+                return (consumed, Scan::Done(Some(nl)));
+            }
+            Resolved::Pending(consumed) => {
+                buf.extend_from_slice(&avail[..consumed]);
+                return (consumed, Scan::Pending);
+            }
+            Resolved::Content(_) => (),
+        }
+        if avail.is_empty() {
+            return (0, Scan::Done(None));
+        }
+        let keep = partial_newline_len(nlset, avail);
+        let complete = avail.len() - keep;
+        if let Some((start, end)) = search_bytes(nlset, &avail[..complete]) {
+            buf.extend_from_slice(&avail[..end]);
+            return (end, Scan::Done(newline_prefix(nlset, &avail[start..])));
+        }
+        buf.extend_from_slice(avail);
+        self.held.set(&avail[complete..]);
+        (avail.len(), Scan::Pending)
+    }
+}
+
+/// An iterator over the lines of a [`BufRead`] value, split on the newline
+/// sequences in a [`NewlineSet`]
+///
+/// This struct is created by [`BufReadNewlineExt::lines_with()`].  See its
+/// documentation for more.
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct BufReadLines<B> {
+    reader: B,
+    nlset: NewlineSet,
+}
+
+impl<B: BufRead> Iterator for BufReadLines<B> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut buf = String::new();
+        match self.reader.read_line_with(&mut buf, self.nlset) {
+            Ok(Some(nl)) => {
+                buf.truncate(buf.len() - nl.len_utf8());
+                Some(Ok(buf))
+            }
+            Ok(None) if buf.is_empty() => None,
+            Ok(None) => Some(Ok(buf)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::NewlineStrExt;
+    use rstest::rstest;
+    use std::io::BufReader;
+
+    #[rstest]
+    #[case("", NewlineSet::UNICODE)]
+    #[case("foo\r\nbar\rbaz\n", NewlineSet::ASCII)]
+    #[case("foo\r\nbar\rbaz\n", NewlineSet::RUST)]
+    #[case("foo\r\nbar\rbaz\n", Newline::CrLf.into())]
+    #[case("\r\r\n\n\r", NewlineSet::ASCII)]
+    #[case("foo\u{2028}\u{0085}bar\u{2029}\r", NewlineSet::UNICODE)]
+    #[case("foo\u{2028}\u{0085}bar\u{2029}\r", NewlineSet::NLF)]
+    fn read_lines(#[case] s: &str, #[case] nlset: NewlineSet) {
+        let expected = nlset.lines_with_newlines(s).collect::<Vec<_>>();
+        for capacity in 1..=4 {
+            let mut reader = BufReader::with_capacity(capacity, s.as_bytes());
+            for line in &expected {
+                let mut buf = String::from("prefix");
+                assert_eq!(
+                    reader.read_line_with(&mut buf, nlset).unwrap(),
+                    line.newline(),
+                    "capacity={capacity}"
+                );
+                assert_eq!(buf, format!("prefix{}", line.as_str()));
+            }
+            let mut buf = String::new();
+            assert_eq!(reader.read_line_with(&mut buf, nlset).unwrap(), None);
+            assert_eq!(buf, "");
+            let reader = BufReader::with_capacity(capacity, s.as_bytes());
+            assert_eq!(
+                reader
+                    .lines_with(nlset)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap(),
+                s.lines_with(nlset).collect::<Vec<_>>(),
+                "capacity={capacity}"
+            );
+        }
+    }
+
+    #[test]
+    fn cr_held_back() {
+        let mut reader = BufReader::with_capacity(4, &b"foo\r\nbar"[..]);
+        let mut buf = String::new();
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::ASCII).unwrap(),
+            Some(Newline::CrLf)
+        );
+        assert_eq!(buf, "foo\r\n");
+        assert_eq!(reader.fill_buf().unwrap(), b"bar");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut reader = &b"foo\xFF\nbar\n"[..];
+        let mut buf = String::from("prefix");
+        let e = reader
+            .read_line_with(&mut buf, NewlineSet::ASCII)
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(buf, "prefix");
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::ASCII).unwrap(),
+            Some(Newline::LineFeed)
+        );
+        assert_eq!(buf, "prefixbar\n");
+    }
+}
@@ -0,0 +1,198 @@
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+
+/// If `s` starts with the UTF-8 encoding of a newline sequence in `nlset`,
+/// returns that newline sequence.  [`Newline::CrLf`] is preferred over
+/// [`Newline::CarriageReturn`] when both match.
+pub(crate) fn newline_prefix(nlset: NewlineSet, s: &[u8]) -> Option<Newline> {
+    if nlset.crlf && s.starts_with(b"\r\n") {
+        return Some(Newline::CrLf);
+    }
+    let nl = match s {
+        [b'\n', ..] => Newline::LineFeed,
+        [0x0B, ..] => Newline::VerticalTab,
+        [0x0C, ..] => Newline::FormFeed,
+        [b'\r', ..] => Newline::CarriageReturn,
+        [0xC2, 0x85, ..] => Newline::NextLine,
+        [0xE2, 0x80, 0xA8, ..] => Newline::LineSeparator,
+        [0xE2, 0x80, 0xA9, ..] => Newline::ParagraphSeparator,
+        _ => return None,
+    };
+    nlset.contains(nl).then_some(nl)
+}
+
+/// Returns the start & end indices of the first UTF-8-encoded newline
+/// sequence in `s` that is in `nlset`
+pub(crate) fn search_bytes(nlset: NewlineSet, s: &[u8]) -> Option<(usize, usize)> {
+    if nlset.is_empty() {
+        return None;
+    }
+    (0..s.len()).find_map(|i| {
+        let nl = newline_prefix(nlset, &s[i..])?;
+        Some((i, i + nl.len_utf8()))
+    })
+}
+
+/// Returns the start & end indices of the last UTF-8-encoded newline sequence
+/// in `s` that is in `nlset`
+pub(crate) fn rsearch_bytes(nlset: NewlineSet, s: &[u8]) -> Option<(usize, usize)> {
+    if nlset.is_empty() {
+        return None;
+    }
+    (0..s.len()).rev().find_map(|i| {
+        if nlset.crlf && s[i] == b'\n' && i > 0 && s[i - 1] == b'\r' {
+            return Some((i - 1, i + 1));
+        }
+        let nl = newline_prefix(nlset, &s[i..])?;
+        Some((i, i + nl.len_utf8()))
+    })
+}
+
+/// If `s` ends with a proper prefix of the UTF-8 encoding of a newline
+/// sequence in `nlset` — i.e., if the end of `s` could be the start of a
+/// newline sequence whose remaining bytes have not been seen yet — returns
+/// the length of that prefix; otherwise, returns 0.
+///
+/// A CR at the end of `s` is treated as such a prefix if `nlset` contains
+/// [`Newline::CrLf`], as it cannot be known whether the CR is a newline on
+/// its own or part of a CR LF pair until the next byte is seen.
+pub(crate) fn partial_newline_len(nlset: NewlineSet, s: &[u8]) -> usize {
+    let ls_ps =
+        nlset.contains(Newline::LineSeparator) || nlset.contains(Newline::ParagraphSeparator);
+    match s {
+        [.., b'\r'] if nlset.crlf => 1,
+        [.., 0xC2] if nlset.contains(Newline::NextLine) => 1,
+        [.., 0xE2, 0x80] if ls_ps => 2,
+        [.., 0xE2] if ls_ps => 1,
+        _ => 0,
+    }
+}
+
+/// Bytes at the end of the input seen so far that may be the start of a
+/// newline sequence, for splitters that hold such bytes back until the
+/// following input shows whether they are part of a newline sequence
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct HeldBytes {
+    /// The held bytes are `bytes[..len]`
+    bytes: [u8; 2],
+    len: usize,
+}
+
+/// The result of [`HeldBytes::resolve()`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Resolved {
+    /// The held bytes followed by the given number of bytes of the input form
+    /// a newline sequence.  This number is zero when a held CR turns out to
+    /// be a bare CR.
+    Newline(Newline, usize),
+
+    /// The held bytes followed by the given number of bytes of the input
+    /// (i.e., all of it) are still a possible prefix of a newline sequence
+    /// and are now held
+    Pending(usize),
+
+    /// The held bytes (which may be empty) are not part of a newline
+    /// sequence and are ordinary content.  None of the input has been
+    /// examined past them.
+    Content(HeldBytes),
+}
+
+impl HeldBytes {
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Hold `bytes`, which must have been found by [`partial_newline_len()`]
+    pub(crate) fn set(&mut self, bytes: &[u8]) {
+        self.bytes[..bytes.len()].copy_from_slice(bytes);
+        self.len = bytes.len();
+    }
+
+    /// Determine what the held bytes are now that `input` follows them.  An
+    /// empty `input` signals the end of the input, at which point a held CR
+    /// is a bare CR and any other held bytes are content.
+    ///
+    /// Unless the result is [`Resolved::Pending`], no bytes are held
+    /// afterwards.
+    pub(crate) fn resolve(&mut self, nlset: NewlineSet, input: &[u8]) -> Resolved {
+        let held = core::mem::take(self);
+        if held.len == 0 {
+            return Resolved::Content(held);
+        }
+        if input.is_empty() {
+            return if held.as_slice() == b"\r" && nlset.contains(Newline::CarriageReturn) {
+                Resolved::Newline(Newline::CarriageReturn, 0)
+            } else {
+                Resolved::Content(held)
+            };
+        }
+        // No newline sequence is more than three bytes long, so only that
+        // much of the held bytes and the input needs to be examined.
+        let n = input.len().min(3 - held.len);
+        let mut window = [0; 3];
+        window[..held.len].copy_from_slice(held.as_slice());
+        window[held.len..(held.len + n)].copy_from_slice(&input[..n]);
+        let window = &window[..(held.len + n)];
+        if let Some(nl) = newline_prefix(nlset, window) {
+            Resolved::Newline(nl, nl.len_utf8() - held.len)
+        } else if partial_newline_len(nlset, window) == window.len() {
+            self.set(window);
+            Resolved::Pending(n)
+        } else {
+            Resolved::Content(held)
+        }
+    }
+}
+
+/// Append `data` to `out`, replacing every UTF-8-encoded newline sequence in
+/// `from` with the encoding of `to`
+#[cfg(feature = "std")]
+pub(crate) fn push_normalized(out: &mut Vec<u8>, data: &[u8], from: NewlineSet, to: Newline) {
+    let mut pos = 0;
+    while let Some((start, end)) = search_bytes(from, &data[pos..]) {
+        out.extend_from_slice(&data[pos..(pos + start)]);
+        out.extend_from_slice(to.as_str().as_bytes());
+        pos += end;
+    }
+    out.extend_from_slice(&data[pos..]);
+}
+
+/// State for converting newline sequences in a stream of bytes that arrives
+/// in chunks
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Converter {
+    from: NewlineSet,
+    to: Newline,
+
+    /// Bytes at the end of the input seen so far that may be the start of a
+    /// newline sequence
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "std")]
+impl Converter {
+    pub(crate) fn new(from: NewlineSet, to: Newline) -> Converter {
+        Converter {
+            from,
+            to,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Convert `input` (preceded by any held-back bytes) and append the
+    /// results to `out`.  If `at_end` is false, any bytes at the end that may
+    /// be the start of a newline sequence are held back.
+    pub(crate) fn convert(&mut self, input: &[u8], at_end: bool, out: &mut Vec<u8>) {
+        let mut data = core::mem::take(&mut self.pending);
+        data.extend_from_slice(input);
+        let keep = if at_end {
+            0
+        } else {
+            partial_newline_len(self.from, &data)
+        };
+        let (complete, rest) = data.split_at(data.len() - keep);
+        push_normalized(out, complete, self.from, self.to);
+        self.pending.extend_from_slice(rest);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::bufread::invalid_utf8;
+use crate::bytes::{newline_prefix, partial_newline_len, search_bytes};
+use crate::errors::NewlineCodecError;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A [`tokio_util::codec`] codec for frames delimited by the UTF-8-encoded
+/// newline sequences in a [`NewlineSet`]
+///
+/// As a [`Decoder`], a `NewlineCodec` splits its input on the newline
+/// sequences in its `NewlineSet` and yields each frame's content (without its
+/// terminating newline sequence) as a [`BytesMut`] paired with the
+/// [`Newline`] that terminated it.  At end of input, any remaining data that
+/// is not terminated by a newline sequence is yielded as a final frame paired
+/// with `None`.  The data need not be valid UTF-8; see
+/// [`NewlineStringCodec`] for a codec that decodes frames to strings.
+///
+/// If a CR at the end of the buffered data could be the start of a CR LF
+/// pair, the frame that it terminates is not yielded until the next byte
+/// arrives (or input ends), so that a CR LF pair split across two reads is
+/// still recognized as a single newline sequence.  Incomplete multi-byte
+/// newline sequences at the end of the buffered data are handled the same
+/// way.
+///
+/// As an [`Encoder`], a `NewlineCodec` writes out each frame followed by the
+/// encoding of its [encoder newline][NewlineCodec::encode_newline], which is
+/// [`Newline::LineFeed`] by default.
+///
+/// By default, frames may be of any length.  A maximum length for the
+/// content of decoded frames can be set with
+/// [`max_frame_length()`][NewlineCodec::max_frame_length]; when a frame
+/// exceeds it, the decoder returns
+/// [`NewlineCodecError::MaxFrameLengthExceeded`], discards the frame, and
+/// resumes decoding after the frame's terminating newline sequence.
+///
+/// The `tokio-util` feature is not covered by this crate's minimum supported
+/// Rust version, as current releases of `tokio-util` require a newer Rust.
+///
+/// # Example
+///
+/// ```
+/// use bytes::BytesMut;
+/// use newlines::{Newline, NewlineCodec, NewlineSet};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = NewlineCodec::new(NewlineSet::ASCII).encode_newline(Newline::CrLf);
+/// let mut buf = BytesMut::from("foo\nbar\r");
+/// assert_eq!(
+///     codec.decode(&mut buf).unwrap(),
+///     Some((BytesMut::from("foo"), Some(Newline::LineFeed)))
+/// );
+/// // The CR might be followed by an LF:
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+/// buf.extend_from_slice(b"\nbaz");
+/// assert_eq!(
+///     codec.decode(&mut buf).unwrap(),
+///     Some((BytesMut::from("bar"), Some(Newline::CrLf)))
+/// );
+/// assert_eq!(
+///     codec.decode_eof(&mut buf).unwrap(),
+///     Some((BytesMut::from("baz"), None))
+/// );
+///
+/// let mut out = BytesMut::new();
+/// codec.encode("quux", &mut out).unwrap();
+/// assert_eq!(out, "quux\r\n");
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NewlineCodec {
+    nlset: NewlineSet,
+    encode_newline: Newline,
+    max_frame_length: usize,
+
+    /// The position in the buffer from which to resume searching for a
+    /// newline sequence
+    next_index: usize,
+
+    /// Whether the rest of the current frame is being discarded because the
+    /// frame is too long
+    discarding: bool,
+}
+
+impl NewlineCodec {
+    /// Create a new `NewlineCodec` that splits frames on the newline sequences
+    /// in `nlset`, using the default configuration
+    pub fn new(nlset: NewlineSet) -> NewlineCodec {
+        NewlineCodec {
+            nlset,
+            encode_newline: Newline::LineFeed,
+            max_frame_length: usize::MAX,
+            next_index: 0,
+            discarding: false,
+        }
+    }
+
+    /// Set the newline sequence that the encoder appends to each frame.  The
+    /// default is [`Newline::LineFeed`].
+    pub fn encode_newline(mut self, nl: Newline) -> NewlineCodec {
+        self.encode_newline = nl;
+        self
+    }
+
+    /// Set the maximum length in bytes of the content of a decoded frame,
+    /// not counting its terminating newline sequence.  The default is
+    /// `usize::MAX`.
+    pub fn max_frame_length(mut self, length: usize) -> NewlineCodec {
+        self.max_frame_length = length;
+        self
+    }
+
+    /// Returns the `NewlineSet` that the decoder splits frames on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns the maximum length of the content of a decoded frame
+    pub fn get_max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Decode the next frame from `src`.  If `eof` is true, no more data will
+    /// be added to `src`, and so held-back bytes at the end of `src` are
+    /// treated as complete.
+    fn decode_frame(
+        &mut self,
+        src: &mut BytesMut,
+        eof: bool,
+    ) -> Result<Option<(BytesMut, Option<Newline>)>, NewlineCodecError> {
+        let nlset = self.nlset;
+        loop {
+            let window = &src[self.next_index..];
+            let keep = if eof {
+                0
+            } else {
+                partial_newline_len(nlset, window)
+            };
+            let Some((start, end)) = search_bytes(nlset, &window[..(window.len() - keep)]) else {
+                let complete = src.len() - keep;
+                if self.discarding {
+                    src.advance(complete);
+                    self.next_index = 0;
+                    if eof {
+                        self.discarding = false;
+                    }
+                    return Ok(None);
+                } else if complete > self.max_frame_length {
+                    src.advance(complete);
+                    self.next_index = 0;
+                    self.discarding = !eof;
+                    return Err(NewlineCodecError::MaxFrameLengthExceeded);
+                } else if eof && !src.is_empty() {
+                    self.next_index = 0;
+                    return Ok(Some((src.split(), None)));
+                } else {
+                    self.next_index = complete;
+                    return Ok(None);
+                }
+            };
+            let nl = newline_prefix(nlset, &window[start..]);
+            let start = self.next_index + start;
+            let end = self.next_index + end;
+            self.next_index = 0;
+            if self.discarding {
+                self.discarding = false;
+                src.advance(end);
+                continue;
+            }
+            let mut frame = src.split_to(end);
+            if start > self.max_frame_length {
+                return Err(NewlineCodecError::MaxFrameLengthExceeded);
+            }
+            frame.truncate(start);
+            return Ok(Some((frame, nl)));
+        }
+    }
+}
+
+impl Decoder for NewlineCodec {
+    type Item = (BytesMut, Option<Newline>);
+    type Error = NewlineCodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, NewlineCodecError> {
+        self.decode_frame(src, false)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, NewlineCodecError> {
+        self.decode_frame(src, true)
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<T> for NewlineCodec {
+    type Error = NewlineCodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), NewlineCodecError> {
+        let item = item.as_ref();
+        let nl = self.encode_newline.as_str().as_bytes();
+        dst.reserve(item.len() + nl.len());
+        dst.put_slice(item);
+        dst.put_slice(nl);
+        Ok(())
+    }
+}
+
+/// A [`tokio_util::codec`] codec for UTF-8 text frames delimited by the
+/// newline sequences in a [`NewlineSet`]
+///
+/// This is the same as [`NewlineCodec`], except that decoded frames are
+/// yielded as `String`s without their terminating newline sequences, and
+/// strings are encoded.  If a decoded frame is not valid UTF-8, the decoder
+/// returns an [`io::ErrorKind::InvalidData`][std::io::ErrorKind::InvalidData]
+/// error.
+///
+/// # Example
+///
+/// ```
+/// use bytes::BytesMut;
+/// use newlines::{NewlineSet, NewlineStringCodec};
+/// use tokio_util::codec::Decoder;
+///
+/// let mut codec = NewlineStringCodec::new(NewlineSet::UNICODE);
+/// let mut buf = BytesMut::from("foo\u{2028}bar\r\n");
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(String::from("foo")));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(String::from("bar")));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NewlineStringCodec(NewlineCodec);
+
+impl NewlineStringCodec {
+    /// Create a new `NewlineStringCodec` that splits frames on the newline
+    /// sequences in `nlset`, using the default configuration
+    pub fn new(nlset: NewlineSet) -> NewlineStringCodec {
+        NewlineStringCodec(NewlineCodec::new(nlset))
+    }
+
+    /// Set the newline sequence that the encoder appends to each frame.  The
+    /// default is [`Newline::LineFeed`].
+    pub fn encode_newline(self, nl: Newline) -> NewlineStringCodec {
+        NewlineStringCodec(self.0.encode_newline(nl))
+    }
+
+    /// Set the maximum length in bytes of the content of a decoded frame,
+    /// not counting its terminating newline sequence.  The default is
+    /// `usize::MAX`.
+    pub fn max_frame_length(self, length: usize) -> NewlineStringCodec {
+        NewlineStringCodec(self.0.max_frame_length(length))
+    }
+
+    /// Returns the `NewlineSet` that the decoder splits frames on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.0.newline_set()
+    }
+
+    /// Returns the maximum length of the content of a decoded frame
+    pub fn get_max_frame_length(&self) -> usize {
+        self.0.get_max_frame_length()
+    }
+}
+
+/// Convert a frame decoded by a [`NewlineCodec`] to a `String`
+fn frame_to_string(
+    frame: Option<(BytesMut, Option<Newline>)>,
+) -> Result<Option<String>, NewlineCodecError> {
+    frame
+        .map(|(content, _)| String::from_utf8(content.into()).map_err(|_| invalid_utf8().into()))
+        .transpose()
+}
+
+impl Decoder for NewlineStringCodec {
+    type Item = String;
+    type Error = NewlineCodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<String>, NewlineCodecError> {
+        frame_to_string(self.0.decode(src)?)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<String>, NewlineCodecError> {
+        frame_to_string(self.0.decode_eof(src)?)
+    }
+}
+
+impl<T: AsRef<str>> Encoder<T> for NewlineStringCodec {
+    type Error = NewlineCodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), NewlineCodecError> {
+        self.0.encode(item.as_ref(), dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// A decoded frame or the `Display` of the error returned in its place
+    type Frame = Result<(Vec<u8>, Option<Newline>), String>;
+
+    /// Feed `data` to `codec` in chunks of `chunk_size` bytes, collecting the
+    /// decoded frames
+    fn decode_chunked(codec: &mut NewlineCodec, data: &[u8], chunk_size: usize) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        let mut buf = BytesMut::new();
+        let mut push = |r: Result<Option<(BytesMut, Option<Newline>)>, NewlineCodecError>| match r {
+            Ok(Some((frame, nl))) => {
+                frames.push(Ok((frame.to_vec(), nl)));
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                frames.push(Err(e.to_string()));
+                true
+            }
+        };
+        for chunk in data.chunks(chunk_size) {
+            buf.extend_from_slice(chunk);
+            while push(codec.decode(&mut buf)) {}
+        }
+        while push(codec.decode_eof(&mut buf)) {}
+        frames
+    }
+
+    #[rstest]
+    #[case(b"", NewlineSet::ASCII, vec![])]
+    #[case(b"foo\r\nbar\rbaz\n", NewlineSet::ASCII, vec![(&b"foo"[..], Some(Newline::CrLf)), (b"bar", Some(Newline::CarriageReturn)), (b"baz", Some(Newline::LineFeed))])]
+    #[case(b"foo\r\nbar\r", Newline::CrLf.into(), vec![(&b"foo"[..], Some(Newline::CrLf)), (b"bar\r", None)])]
+    #[case(b"\r\r\n\xFF\xC2\x85", NewlineSet::UNICODE, vec![(&b""[..], Some(Newline::CarriageReturn)), (b"", Some(Newline::CrLf)), (b"\xFF", Some(Newline::NextLine))])]
+    #[case(b"a\xE2\x80\xA8b\xE2\x80", NewlineSet::UNICODE, vec![(&b"a"[..], Some(Newline::LineSeparator)), (b"b\xE2\x80", None)])]
+    fn decode(
+        #[case] data: &[u8],
+        #[case] nlset: NewlineSet,
+        #[case] frames: Vec<(&[u8], Option<Newline>)>,
+    ) {
+        let frames = frames
+            .into_iter()
+            .map(|(frame, nl)| Ok((frame.to_vec(), nl)))
+            .collect::<Vec<_>>();
+        for chunk_size in 1..=4 {
+            let mut codec = NewlineCodec::new(nlset);
+            assert_eq!(
+                decode_chunked(&mut codec, data, chunk_size),
+                frames,
+                "chunk_size={chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn max_frame_length() {
+        for chunk_size in [1, 2, 3, 100] {
+            let mut codec = NewlineCodec::new(NewlineSet::ASCII).max_frame_length(3);
+            assert_eq!(
+                decode_chunked(&mut codec, b"foo\r\nquux\r\nbar\nbazzz", chunk_size),
+                [
+                    Ok((b"foo".to_vec(), Some(Newline::CrLf))),
+                    Err(String::from("max frame length exceeded")),
+                    Ok((b"bar".to_vec(), Some(Newline::LineFeed))),
+                    Err(String::from("max frame length exceeded")),
+                ],
+                "chunk_size={chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn string_codec() {
+        let mut codec = NewlineStringCodec::new(NewlineSet::ASCII).encode_newline(Newline::CrLf);
+        let mut buf = BytesMut::from(&b"foo\n\xFF\nbar"[..]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), Some(String::from("foo")));
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(NewlineCodecError::Io(e)) if e.kind() == std::io::ErrorKind::InvalidData
+        ));
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        assert_eq!(
+            codec.decode_eof(&mut buf).unwrap(),
+            Some(String::from("bar"))
+        );
+        assert_eq!(codec.decode_eof(&mut buf).unwrap(), None);
+        let mut out = BytesMut::new();
+        codec.encode("foo", &mut out).unwrap();
+        codec.encode(String::from("bar"), &mut out).unwrap();
+        assert_eq!(out, "foo\r\nbar\r\n");
+    }
+}
@@ -0,0 +1,175 @@
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+
+/// A classification of the newline sequences used in a piece of text, as
+/// returned by [`detect()`] and [`detect_prefix()`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LineEndingStyle {
+    /// The only newline sequence used is [`Newline::LineFeed`]
+    Unix,
+
+    /// The only newline sequence used is [`Newline::CrLf`]
+    Windows,
+
+    /// The only newline sequence used is [`Newline::CarriageReturn`]
+    ClassicMac,
+
+    /// The only newline sequence used is the given [`Newline`], which is
+    /// neither [`Newline::LineFeed`], [`Newline::CrLf`], nor
+    /// [`Newline::CarriageReturn`] (e.g., [`Newline::FormFeed`] or
+    /// [`Newline::LineSeparator`])
+    Other(Newline),
+
+    /// Multiple newline sequences are used; the set of all of them is
+    /// included
+    Mixed(NewlineSet),
+
+    /// No newline sequences are used
+    None,
+}
+
+impl LineEndingStyle {
+    /// Classify text based on the set of newline sequences that occur in it
+    pub fn from_newline_set(present: NewlineSet) -> LineEndingStyle {
+        let mut iter = present.iter();
+        match (iter.next(), iter.next()) {
+            (None, _) => LineEndingStyle::None,
+            (Some(Newline::LineFeed), None) => LineEndingStyle::Unix,
+            (Some(Newline::CrLf), None) => LineEndingStyle::Windows,
+            (Some(Newline::CarriageReturn), None) => LineEndingStyle::ClassicMac,
+            (Some(nl), None) => LineEndingStyle::Other(nl),
+            (Some(_), Some(_)) => LineEndingStyle::Mixed(present),
+        }
+    }
+
+    /// If the style uses exactly one newline sequence, returns that newline
+    /// sequence
+    pub fn newline(&self) -> Option<Newline> {
+        match self {
+            LineEndingStyle::Unix => Some(Newline::LineFeed),
+            LineEndingStyle::Windows => Some(Newline::CrLf),
+            LineEndingStyle::ClassicMac => Some(Newline::CarriageReturn),
+            LineEndingStyle::Other(nl) => Some(*nl),
+            LineEndingStyle::Mixed(_) | LineEndingStyle::None => None,
+        }
+    }
+}
+
+/// Determine the line-ending style of `s` based on which [`Newline`] variants
+/// occur in it.
+///
+/// All newline sequences recognized by this library (i.e., those in
+/// [`NewlineSet::ALL`]) are considered, with CR LF pairs counting as
+/// [`Newline::CrLf`] rather than as a CR and an LF.  To only consider some
+/// newline sequences, call [`NewlineSet::present_in()`] on the desired set
+/// and pass the result to [`LineEndingStyle::from_newline_set()`].
+///
+/// # Example
+///
+/// ```
+/// use newlines::{detect, LineEndingStyle, Newline};
+///
+/// assert_eq!(detect("foo\nbar\n"), LineEndingStyle::Unix);
+/// assert_eq!(detect("foo\r\nbar\r\n"), LineEndingStyle::Windows);
+/// assert_eq!(
+///     detect("foo\u{2028}bar"),
+///     LineEndingStyle::Other(Newline::LineSeparator),
+/// );
+/// assert_eq!(
+///     detect("foo\r\nbar\n"),
+///     LineEndingStyle::Mixed(Newline::LineFeed | Newline::CrLf),
+/// );
+/// assert_eq!(detect("foobar"), LineEndingStyle::None);
+/// ```
+pub fn detect(s: &str) -> LineEndingStyle {
+    LineEndingStyle::from_newline_set(NewlineSet::ALL.present_in(s))
+}
+
+/// Determine the line-ending style of `s` by examining only (approximately)
+/// its first `limit` bytes.
+///
+/// This is the same as [`detect()`] applied to a prefix of `s`.  The prefix
+/// is shortened as needed to end on a `char` boundary, and it is lengthened
+/// by one byte if it would otherwise split a CR LF pair.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{detect_prefix, LineEndingStyle};
+///
+/// let s = "foo\r\nbar\r\nbaz\n";
+/// assert_eq!(detect_prefix(s, 10), LineEndingStyle::Windows);
+/// assert_eq!(detect_prefix(s, 4), LineEndingStyle::Windows);
+/// assert!(matches!(detect_prefix(s, 100), LineEndingStyle::Mixed(_)));
+/// ```
+pub fn detect_prefix(s: &str, limit: usize) -> LineEndingStyle {
+    detect(&s[..prefix_end(s, limit)])
+}
+
+fn prefix_end(s: &str, limit: usize) -> usize {
+    if limit >= s.len() {
+        return s.len();
+    }
+    let mut end = limit;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    if s[..end].ends_with('\r') && s[end..].starts_with('\n') {
+        end += 1;
+    }
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", LineEndingStyle::None)]
+    #[case("foo", LineEndingStyle::None)]
+    #[case("foo\n", LineEndingStyle::Unix)]
+    #[case("foo\nbar\n\n", LineEndingStyle::Unix)]
+    #[case("foo\r\nbar", LineEndingStyle::Windows)]
+    #[case("foo\rbar\r", LineEndingStyle::ClassicMac)]
+    #[case("foo\u{0085}", LineEndingStyle::Other(Newline::NextLine))]
+    #[case(
+        "foo\u{2029}bar\u{2029}",
+        LineEndingStyle::Other(Newline::ParagraphSeparator)
+    )]
+    #[case("foo\x0C", LineEndingStyle::Other(Newline::FormFeed))]
+    #[case("foo\x0Bbar", LineEndingStyle::Other(Newline::VerticalTab))]
+    #[case("foo\r\n\r", LineEndingStyle::Mixed(Newline::CarriageReturn | Newline::CrLf))]
+    #[case("foo\n\r", LineEndingStyle::Mixed(Newline::LineFeed | Newline::CarriageReturn))]
+    #[case("foo\n\u{2028}", LineEndingStyle::Mixed(Newline::LineFeed | Newline::LineSeparator))]
+    fn test_detect(#[case] s: &str, #[case] style: LineEndingStyle) {
+        assert_eq!(detect(s), style);
+    }
+
+    #[rstest]
+    #[case("foo\r\nbar", 0, 0)]
+    #[case("foo\r\nbar", 3, 3)]
+    #[case("foo\r\nbar", 4, 5)]
+    #[case("foo\r\nbar", 5, 5)]
+    #[case("foo\r\nbar", 100, 8)]
+    #[case("foo\u{2028}bar", 4, 3)]
+    #[case("foo\u{2028}bar", 5, 3)]
+    #[case("foo\u{2028}bar", 6, 6)]
+    fn test_prefix_end(#[case] s: &str, #[case] limit: usize, #[case] end: usize) {
+        assert_eq!(prefix_end(s, limit), end);
+    }
+
+    #[rstest]
+    #[case(LineEndingStyle::Unix, Some(Newline::LineFeed))]
+    #[case(LineEndingStyle::Windows, Some(Newline::CrLf))]
+    #[case(LineEndingStyle::ClassicMac, Some(Newline::CarriageReturn))]
+    #[case(LineEndingStyle::Other(Newline::NextLine), Some(Newline::NextLine))]
+    #[case(LineEndingStyle::Mixed(NewlineSet::ASCII), None)]
+    #[case(LineEndingStyle::None, None)]
+    fn test_newline(#[case] style: LineEndingStyle, #[case] nl: Option<Newline>) {
+        assert_eq!(style.newline(), nl);
+        if let Some(nl) = nl {
+            assert_eq!(LineEndingStyle::from_newline_set(nl.into()), style);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::assembler::LineAssembler;
+use crate::embedded_io_ext::{AssemblerFeeder, ReadLineResult, Scan, SliceLineScanner};
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use embedded_io_async::{BufRead, Read, Write};
+
+/// Extension trait for reading lines from an [`embedded_io_async::BufRead`]
+/// value into a caller-supplied buffer, split on the UTF-8-encoded newline
+/// sequences in a [`NewlineSet`]
+///
+/// This is the asynchronous counterpart of
+/// [`EmbeddedBufReadNewlineExt`][crate::EmbeddedBufReadNewlineExt], and it
+/// provides the same guarantees: newline sequences that are split across
+/// multiple fills of the reader's buffer are still recognized as a single
+/// unit, and a CR at the end of a buffer fill that could be the start of a CR
+/// LF pair is held back until the next byte has been read.
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io_async::BufRead`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedAsyncBufReadNewlineExt, Newline, NewlineSet};
+///
+/// # futures_executor::block_on(async {
+/// let mut reader = &b"AT\r\nOK\r\n"[..];
+/// let mut buf = [0; 16];
+/// assert_eq!(
+///     reader.read_line_with(&mut buf, NewlineSet::ASCII).await,
+///     Ok(Some(("AT", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(
+///     reader.read_line_with(&mut buf, NewlineSet::ASCII).await,
+///     Ok(Some(("OK", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(
+///     reader.read_line_with(&mut buf, NewlineSet::ASCII).await,
+///     Ok(None)
+/// );
+/// # })
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io-async")))]
+#[allow(async_fn_in_trait)]
+pub trait EmbeddedAsyncBufReadNewlineExt: BufRead {
+    /// Read bytes from the reader until a newline sequence in `nlset` or end
+    /// of file is reached, and store the content of the line (without the
+    /// newline sequence) at the start of `buf`.
+    ///
+    /// Returns the content of the line along with the [`Newline`] that
+    /// terminated it, or `None` in place of the `Newline` if end of file was
+    /// reached first.  Returns `Ok(None)` if the reader was already at end of
+    /// file.
+    ///
+    /// This future is not cancellation safe: if it is dropped before it
+    /// completes, any data that it has read is lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadLineError::BufferFull`][crate::errors::ReadLineError::BufferFull]
+    /// if the content of the line does not fit in `buf`,
+    /// [`ReadLineError::InvalidUtf8`][crate::errors::ReadLineError::InvalidUtf8]
+    /// if the content of the line is not valid UTF-8, or
+    /// [`ReadLineError::Io`][crate::errors::ReadLineError::Io] if the reader
+    /// returns an error.  In the first two cases, the entire line has been
+    /// consumed from the reader.
+    async fn read_line_with<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+        nlset: NewlineSet,
+    ) -> ReadLineResult<'a, Self::Error>;
+}
+
+impl<R: BufRead + ?Sized> EmbeddedAsyncBufReadNewlineExt for R {
+    async fn read_line_with<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+        nlset: NewlineSet,
+    ) -> ReadLineResult<'a, Self::Error> {
+        let mut scanner = SliceLineScanner::new(nlset);
+        loop {
+            let avail = self.fill_buf().await?;
+            let (consumed, scan) = scanner.scan(buf, avail);
+            self.consume(consumed);
+            if let Scan::Done(nl) = scan {
+                return scanner.finish(buf, nl);
+            }
+        }
+    }
+}
+
+/// Extension trait for reading lines from an unbuffered
+/// [`embedded_io_async::Read`] value, one byte at a time, into a
+/// caller-supplied [`LineAssembler`]
+///
+/// This is the asynchronous counterpart of
+/// [`EmbeddedReadNewlineExt`][crate::EmbeddedReadNewlineExt]; see its
+/// documentation for details.
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io_async::Read`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedAsyncReadNewlineExt, LineAssembler, Newline, NewlineSet};
+///
+/// # futures_executor::block_on(async {
+/// let mut reader = &b"AT\r\nOK"[..];
+/// let mut asm = LineAssembler::<16>::new(NewlineSet::ASCII);
+/// assert_eq!(
+///     reader.read_line_into(&mut asm).await,
+///     Ok(Some(("AT", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(reader.read_line_into(&mut asm).await, Ok(Some(("OK", None))));
+/// assert_eq!(reader.read_line_into(&mut asm).await, Ok(None));
+/// # })
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io-async")))]
+#[allow(async_fn_in_trait)]
+pub trait EmbeddedAsyncReadNewlineExt: Read {
+    /// Read bytes from the reader one at a time and feed them to `asm` until
+    /// it completes a line or end of file is reached.
+    ///
+    /// Returns the content of the line (stored in `asm`'s buffer) along with
+    /// the [`Newline`] that terminated it, or `None` in place of the
+    /// `Newline` if end of file was reached first.  Returns `Ok(None)` if the
+    /// reader was already at end of file and `asm` holds no further lines.
+    ///
+    /// This future is cancellation safe in the sense that no byte read from
+    /// the reader is lost if it is dropped, as every byte is fed to `asm`
+    /// as soon as it is read; however, if the current line overflowed
+    /// `asm`'s buffer, the next call will not report the overflow.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadLineError::BufferFull`][crate::errors::ReadLineError::BufferFull]
+    /// if the content of the line does not fit in `asm`'s buffer,
+    /// [`ReadLineError::InvalidUtf8`][crate::errors::ReadLineError::InvalidUtf8]
+    /// if the content of the line is not valid UTF-8, or
+    /// [`ReadLineError::Io`][crate::errors::ReadLineError::Io] if the reader
+    /// returns an error.  In the first two cases, the entire line has been
+    /// consumed from the reader.
+    async fn read_line_into<'a, const N: usize>(
+        &mut self,
+        asm: &'a mut LineAssembler<N>,
+    ) -> ReadLineResult<'a, Self::Error>;
+}
+
+impl<R: Read + ?Sized> EmbeddedAsyncReadNewlineExt for R {
+    async fn read_line_into<'a, const N: usize>(
+        &mut self,
+        asm: &'a mut LineAssembler<N>,
+    ) -> ReadLineResult<'a, Self::Error> {
+        let mut feeder = AssemblerFeeder::default();
+        loop {
+            let mut byte = [0];
+            if self.read(&mut byte).await? == 0 {
+                return feeder.finish(asm);
+            }
+            if let Some(nl) = feeder.feed(asm, byte[0])? {
+                return Ok(Some((asm.line(), Some(nl))));
+            }
+        }
+    }
+}
+
+/// Extension trait for writing lines terminated by a given [`Newline`] to an
+/// [`embedded_io_async::Write`] value
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io_async::Write`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedAsyncWriteNewlineExt, Newline};
+///
+/// # futures_executor::block_on(async {
+/// let mut buf = [0; 16];
+/// let mut writer = &mut buf[..];
+/// writer.write_line("AT+CSQ", Newline::CrLf).await.unwrap();
+/// assert_eq!(writer.len(), 8);
+/// assert_eq!(&buf[..8], b"AT+CSQ\r\n");
+/// # })
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io-async")))]
+#[allow(async_fn_in_trait)]
+pub trait EmbeddedAsyncWriteNewlineExt: Write {
+    /// Write `line` to the writer followed by the UTF-8 encoding of
+    /// `newline`
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by [`Write::write_all()`].
+    async fn write_line(&mut self, line: &str, newline: Newline) -> Result<(), Self::Error>;
+}
+
+impl<W: Write + ?Sized> EmbeddedAsyncWriteNewlineExt for W {
+    async fn write_line(&mut self, line: &str, newline: Newline) -> Result<(), Self::Error> {
+        self.write_all(line.as_bytes()).await?;
+        self.write_all(newline.as_str().as_bytes()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedded_io_ext::tests::{read_lines_cases, ChunkReader};
+    use crate::errors::ReadLineError;
+    use futures_executor::block_on;
+    use rstest::rstest;
+
+    read_lines_cases! {
+        fn read_lines(#[case] s: &str, #[case] nlset: NewlineSet) {
+            let expected = nlset.lines_with_newlines(s).collect::<Vec<_>>();
+            for capacity in 1..=4 {
+                block_on(async {
+                    let mut reader = ChunkReader {
+                        data: s.as_bytes(),
+                        capacity,
+                    };
+                    let mut buf = [0; 16];
+                    for line in &expected {
+                        assert_eq!(
+                            reader.read_line_with(&mut buf, nlset).await,
+                            Ok(Some((line.content(), line.newline()))),
+                            "capacity={capacity}"
+                        );
+                    }
+                    assert_eq!(reader.read_line_with(&mut buf, nlset).await, Ok(None));
+                });
+            }
+        }
+    }
+
+    read_lines_cases! {
+        fn read_lines_into(#[case] s: &str, #[case] nlset: NewlineSet) {
+            block_on(async {
+                let mut reader = s.as_bytes();
+                let mut asm = LineAssembler::<16>::new(nlset);
+                for line in nlset.lines_with_newlines(s) {
+                    assert_eq!(
+                        reader.read_line_into(&mut asm).await,
+                        Ok(Some((line.content(), line.newline())))
+                    );
+                }
+                assert_eq!(reader.read_line_into(&mut asm).await, Ok(None));
+            });
+        }
+    }
+
+    #[test]
+    fn read_line_into_errors() {
+        block_on(async {
+            let mut reader = &b"1234\r\n12345\r\nf\xFF\nok\n123456"[..];
+            let mut asm = LineAssembler::<4>::new(NewlineSet::ASCII);
+            assert_eq!(
+                reader.read_line_into(&mut asm).await,
+                Ok(Some(("1234", Some(Newline::CrLf))))
+            );
+            assert_eq!(
+                reader.read_line_into(&mut asm).await,
+                Err(ReadLineError::BufferFull)
+            );
+            assert!(matches!(
+                reader.read_line_into(&mut asm).await,
+                Err(ReadLineError::InvalidUtf8(_))
+            ));
+            assert_eq!(
+                reader.read_line_into(&mut asm).await,
+                Ok(Some(("ok", Some(Newline::LineFeed))))
+            );
+            assert_eq!(
+                reader.read_line_into(&mut asm).await,
+                Err(ReadLineError::BufferFull)
+            );
+            assert_eq!(reader.read_line_into(&mut asm).await, Ok(None));
+        });
+    }
+
+    #[test]
+    fn buffer_full_and_write_line() {
+        block_on(async {
+            let mut reader = &b"12345\r\nok\r\n"[..];
+            let mut buf = [0; 4];
+            assert_eq!(
+                reader.read_line_with(&mut buf, NewlineSet::ASCII).await,
+                Err(ReadLineError::BufferFull)
+            );
+            assert_eq!(
+                reader.read_line_with(&mut buf, NewlineSet::ASCII).await,
+                Ok(Some(("ok", Some(Newline::CrLf))))
+            );
+            let mut out = [0; 8];
+            let mut writer = &mut out[..];
+            assert_eq!(writer.write_line("ok", Newline::CrLf).await, Ok(()));
+            assert_eq!(writer.len(), 4);
+            assert_eq!(&out[..4], b"ok\r\n");
+        });
+    }
+}
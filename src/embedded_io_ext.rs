@@ -0,0 +1,529 @@
+use crate::assembler::LineAssembler;
+use crate::bytes::{newline_prefix, partial_newline_len, search_bytes, HeldBytes, Resolved};
+use crate::errors::{LineAssemblerError, ReadLineError};
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use embedded_io::{BufRead, Read, Write};
+
+/// Extension trait for reading lines from an [`embedded_io::BufRead`] value
+/// into a caller-supplied buffer, split on the UTF-8-encoded newline
+/// sequences in a [`NewlineSet`]
+///
+/// This is the `no_std` counterpart of
+/// [`BufReadNewlineExt`][crate::BufReadNewlineExt].  Newline sequences that
+/// are split across multiple fills of the reader's buffer are still recognized
+/// as a single unit, and a CR at the end of a buffer fill that could be the
+/// start of a CR LF pair is held back until the next byte has been read.
+///
+/// To read lines from an unbuffered [`embedded_io::Read`] value, use
+/// [`EmbeddedReadNewlineExt`] instead.
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io::BufRead`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedBufReadNewlineExt, Newline, NewlineSet};
+///
+/// let mut reader = &b"AT\r\nOK\r\n"[..];
+/// let mut buf = [0; 16];
+/// assert_eq!(
+///     reader.read_line_with(&mut buf, NewlineSet::ASCII),
+///     Ok(Some(("AT", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(
+///     reader.read_line_with(&mut buf, NewlineSet::ASCII),
+///     Ok(Some(("OK", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(reader.read_line_with(&mut buf, NewlineSet::ASCII), Ok(None));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub trait EmbeddedBufReadNewlineExt: BufRead {
+    /// Read bytes from the reader until a newline sequence in `nlset` or end
+    /// of file is reached, and store the content of the line (without the
+    /// newline sequence) at the start of `buf`.
+    ///
+    /// Returns the content of the line along with the [`Newline`] that
+    /// terminated it, or `None` in place of the `Newline` if end of file was
+    /// reached first.  Returns `Ok(None)` if the reader was already at end of
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadLineError::BufferFull`] if the content of the line does
+    /// not fit in `buf`, [`ReadLineError::InvalidUtf8`] if the content of the
+    /// line is not valid UTF-8, or [`ReadLineError::Io`] if the reader
+    /// returns an error.  In the first two cases, the entire line has been
+    /// consumed from the reader.
+    fn read_line_with<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+        nlset: NewlineSet,
+    ) -> ReadLineResult<'a, Self::Error>;
+}
+
+impl<R: BufRead + ?Sized> EmbeddedBufReadNewlineExt for R {
+    fn read_line_with<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+        nlset: NewlineSet,
+    ) -> ReadLineResult<'a, Self::Error> {
+        let mut scanner = SliceLineScanner::new(nlset);
+        loop {
+            let avail = self.fill_buf()?;
+            let (consumed, scan) = scanner.scan(buf, avail);
+            self.consume(consumed);
+            if let Scan::Done(nl) = scan {
+                return scanner.finish(buf, nl);
+            }
+        }
+    }
+}
+
+/// Extension trait for reading lines from an unbuffered [`embedded_io::Read`]
+/// value, one byte at a time, into a caller-supplied [`LineAssembler`]
+///
+/// The assembler's buffer holds the content of each line, and its
+/// [`NewlineSet`] determines where lines are split.  Because an unbuffered
+/// reader cannot be peeked, any bytes read past the end of a line (such as
+/// the byte after a bare CR when the set contains [`Newline::CrLf`]) are kept
+/// in the assembler, and so the same assembler should be passed to every call
+/// for a given reader.
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io::Read`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedReadNewlineExt, LineAssembler, Newline, NewlineSet};
+///
+/// let mut reader = &b"AT\r\nOK\r"[..];
+/// let mut asm = LineAssembler::<16>::new(NewlineSet::ASCII);
+/// assert_eq!(
+///     reader.read_line_into(&mut asm),
+///     Ok(Some(("AT", Some(Newline::CrLf))))
+/// );
+/// assert_eq!(
+///     reader.read_line_into(&mut asm),
+///     Ok(Some(("OK", Some(Newline::CarriageReturn))))
+/// );
+/// assert_eq!(reader.read_line_into(&mut asm), Ok(None));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub trait EmbeddedReadNewlineExt: Read {
+    /// Read bytes from the reader one at a time and feed them to `asm` until
+    /// it completes a line or end of file is reached.
+    ///
+    /// Returns the content of the line (stored in `asm`'s buffer) along with
+    /// the [`Newline`] that terminated it, or `None` in place of the
+    /// `Newline` if end of file was reached first.  Returns `Ok(None)` if the
+    /// reader was already at end of file and `asm` holds no further lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadLineError::BufferFull`] if the content of the line does
+    /// not fit in `asm`'s buffer, [`ReadLineError::InvalidUtf8`] if the
+    /// content of the line is not valid UTF-8, or [`ReadLineError::Io`] if
+    /// the reader returns an error.  In the first two cases, the entire line
+    /// has been consumed from the reader.
+    fn read_line_into<'a, const N: usize>(
+        &mut self,
+        asm: &'a mut LineAssembler<N>,
+    ) -> ReadLineResult<'a, Self::Error>;
+}
+
+impl<R: Read + ?Sized> EmbeddedReadNewlineExt for R {
+    fn read_line_into<'a, const N: usize>(
+        &mut self,
+        asm: &'a mut LineAssembler<N>,
+    ) -> ReadLineResult<'a, Self::Error> {
+        let mut feeder = AssemblerFeeder::default();
+        loop {
+            let mut byte = [0];
+            if self.read(&mut byte)? == 0 {
+                return feeder.finish(asm);
+            }
+            if let Some(nl) = feeder.feed(asm, byte[0])? {
+                return Ok(Some((asm.line(), Some(nl))));
+            }
+        }
+    }
+}
+
+/// The result of reading a line into a caller-supplied buffer: the content of
+/// the line and the newline sequence that terminated it (or `None` if end of
+/// file was reached first), or `None` if the reader was already at end of
+/// file
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub type ReadLineResult<'a, E> = Result<Option<(&'a str, Option<Newline>)>, ReadLineError<E>>;
+
+/// Extension trait for writing lines terminated by a given [`Newline`] to an
+/// [`embedded_io::Write`] value
+///
+/// This trait is implemented for all types that implement
+/// `embedded_io::Write`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{EmbeddedWriteNewlineExt, Newline};
+///
+/// # fn main() -> Result<(), embedded_io::SliceWriteError> {
+/// let mut buf = [0; 16];
+/// let mut writer = &mut buf[..];
+/// writer.write_line("AT+CSQ", Newline::CrLf)?;
+/// assert_eq!(writer.len(), 8);
+/// assert_eq!(&buf[..8], b"AT+CSQ\r\n");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+pub trait EmbeddedWriteNewlineExt: Write {
+    /// Write `line` to the writer followed by the UTF-8 encoding of
+    /// `newline`
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by [`Write::write_all()`].
+    fn write_line(&mut self, line: &str, newline: Newline) -> Result<(), Self::Error>;
+}
+
+impl<W: Write + ?Sized> EmbeddedWriteNewlineExt for W {
+    fn write_line(&mut self, line: &str, newline: Newline) -> Result<(), Self::Error> {
+        self.write_all(line.as_bytes())?;
+        self.write_all(newline.as_str().as_bytes())
+    }
+}
+
+/// State for reading a line from a buffered reader into a fixed-size buffer
+/// one fill of the reader's buffer at a time
+#[derive(Clone, Debug)]
+pub(crate) struct SliceLineScanner {
+    nlset: NewlineSet,
+
+    /// The length of the content of the line stored in the buffer so far
+    len: usize,
+
+    /// Bytes that have been consumed from the reader and that may be the
+    /// start of a newline sequence
+    held: HeldBytes,
+
+    /// Whether any data has been read from the reader
+    started: bool,
+
+    /// Whether the content of the line has overflowed the buffer
+    overflowed: bool,
+}
+
+/// The outcome of passing a fill of a reader's buffer to
+/// [`SliceLineScanner::scan()`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Scan {
+    /// The end of the line has not been reached yet
+    Pending,
+
+    /// The end of the line was reached.  The field is the newline sequence
+    /// that terminated the line, or `None` if the line was terminated by end
+    /// of file.
+    Done(Option<Newline>),
+}
+
+impl SliceLineScanner {
+    pub(crate) fn new(nlset: NewlineSet) -> SliceLineScanner {
+        SliceLineScanner {
+            nlset,
+            len: 0,
+            held: HeldBytes::default(),
+            started: false,
+            overflowed: false,
+        }
+    }
+
+    /// Store the content in `avail` (the contents of a reader's buffer, which
+    /// is empty at end of file) up through the end of the current line in
+    /// `buf`.  Returns the number of bytes of `avail` that the caller must
+    /// consume from the reader, along with whether the end of the line was
+    /// reached.
+    pub(crate) fn scan(&mut self, buf: &mut [u8], avail: &[u8]) -> (usize, Scan) {
+        let nlset = self.nlset;
+        self.started |= !avail.is_empty();
+        match self.held.resolve(nlset, avail) {
+            Resolved::Newline(nl, consumed) => return (consumed, Scan::Done(Some(nl))),
+            Resolved::Pending(consumed) => return (consumed, Scan::Pending),
+            Resolved::Content(held) => self.push_content(buf, held.as_slice()),
+        }
+        if avail.is_empty() {
+            return (0, Scan::Done(None));
+        }
+        let keep = partial_newline_len(nlset, avail);
+        let complete = avail.len() - keep;
+        if let Some((start, end)) = search_bytes(nlset, &avail[..complete]) {
+            self.push_content(buf, &avail[..start]);
+            return (end, Scan::Done(newline_prefix(nlset, &avail[start..])));
+        }
+        self.push_content(buf, &avail[..complete]);
+        self.held.set(&avail[complete..]);
+        (avail.len(), Scan::Pending)
+    }
+
+    /// Convert the content stored in `buf` and the newline sequence that
+    /// terminated the line into the result of a `read_line_with()` call
+    pub(crate) fn finish<'a, E>(
+        &self,
+        buf: &'a [u8],
+        nl: Option<Newline>,
+    ) -> ReadLineResult<'a, E> {
+        if self.overflowed {
+            Err(ReadLineError::BufferFull)
+        } else if nl.is_none() && !self.started {
+            Ok(None)
+        } else {
+            match core::str::from_utf8(&buf[..self.len]) {
+                Ok(s) => Ok(Some((s, nl))),
+                Err(e) => Err(ReadLineError::InvalidUtf8(e)),
+            }
+        }
+    }
+
+    /// Append `bytes` to the content of the line in `buf`
+    fn push_content(&mut self, buf: &mut [u8], bytes: &[u8]) {
+        if self.overflowed {
+            return;
+        }
+        match buf.get_mut(self.len..(self.len + bytes.len())) {
+            Some(dest) => {
+                dest.copy_from_slice(bytes);
+                self.len += bytes.len();
+            }
+            None => self.overflowed = true,
+        }
+    }
+}
+
+/// State for reading a line from an unbuffered reader by feeding bytes to a
+/// [`LineAssembler`]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct AssemblerFeeder {
+    /// Whether the content of the line has overflowed the assembler's buffer
+    overflowed: bool,
+}
+
+impl AssemblerFeeder {
+    /// Feed a byte read from the reader to `asm`.  Returns the newline
+    /// sequence that terminated the line if the byte completed one, in which
+    /// case the content of the line can be retrieved with
+    /// [`LineAssembler::line()`].
+    pub(crate) fn feed<E, const N: usize>(
+        &mut self,
+        asm: &mut LineAssembler<N>,
+        byte: u8,
+    ) -> Result<Option<Newline>, ReadLineError<E>> {
+        match asm.feed_newline(byte) {
+            Ok(Some(nl)) => Ok(Some(nl)),
+            Ok(None) if self.overflowed && !asm.is_discarding() => Err(ReadLineError::BufferFull),
+            Ok(None) => Ok(None),
+            Err(LineAssemblerError::Overflow) => {
+                self.overflowed = true;
+                Ok(None)
+            }
+            Err(LineAssemblerError::InvalidUtf8(e)) => Err(ReadLineError::InvalidUtf8(e)),
+        }
+    }
+
+    /// Resolve the data remaining in `asm` once the reader has reached end of
+    /// file into the result of a `read_line_into()` call
+    pub(crate) fn finish<'a, E, const N: usize>(
+        &self,
+        asm: &'a mut LineAssembler<N>,
+    ) -> ReadLineResult<'a, E> {
+        if self.overflowed {
+            asm.reset();
+            return Err(ReadLineError::BufferFull);
+        }
+        match asm.finish() {
+            Ok(line) => Ok(line),
+            Err(LineAssemblerError::Overflow) => Err(ReadLineError::BufferFull),
+            Err(LineAssemblerError::InvalidUtf8(e)) => Err(ReadLineError::InvalidUtf8(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embedded_io::ErrorType;
+    use rstest::rstest;
+
+    /// A reader that returns at most `capacity` bytes per fill of its buffer
+    #[derive(Clone, Debug)]
+    pub(crate) struct ChunkReader<'a> {
+        pub(crate) data: &'a [u8],
+        pub(crate) capacity: usize,
+    }
+
+    impl ErrorType for ChunkReader<'_> {
+        type Error = Infallible;
+    }
+
+    impl BufRead for ChunkReader<'_> {
+        fn fill_buf(&mut self) -> Result<&[u8], Infallible> {
+            Ok(&self.data[..self.data.len().min(self.capacity)])
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.data = &self.data[amt..];
+        }
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    impl embedded_io_async::BufRead for ChunkReader<'_> {
+        async fn fill_buf(&mut self) -> Result<&[u8], Infallible> {
+            BufRead::fill_buf(self)
+        }
+
+        fn consume(&mut self, amt: usize) {
+            BufRead::consume(self, amt);
+        }
+    }
+
+    /// Apply the `#[rstest]` cases for the tests of reading whole inputs
+    /// line by line, shared by the blocking and async tests
+    macro_rules! read_lines_cases {
+        ($test:item) => {
+            #[rstest]
+            #[case("", NewlineSet::UNICODE)]
+            #[case("foo\r\nbar\rbaz\n", NewlineSet::ASCII)]
+            #[case("foo\r\nbar\rbaz\n", Newline::CrLf.into())]
+            #[case("\r\r\n\n\r", NewlineSet::ASCII)]
+            #[case("a\r\x0C", NewlineSet::UNICODE)]
+            #[case("foo\u{2028}\u{0085}bar\u{2029}\r", NewlineSet::UNICODE)]
+            #[case("foo\u{2028}\u{0085}bar\u{2029}\r", NewlineSet::NLF)]
+            #[case("caf\u{e9}\u{2028}\u{20ac}\u{0085}", NewlineSet::UNICODE)]
+            $test
+        };
+    }
+
+    #[cfg(feature = "embedded-io-async")]
+    pub(crate) use read_lines_cases;
+
+    read_lines_cases! {
+        fn read_lines(#[case] s: &str, #[case] nlset: NewlineSet) {
+            let expected = nlset.lines_with_newlines(s).collect::<Vec<_>>();
+            for capacity in 1..=4 {
+                let mut reader = ChunkReader {
+                    data: s.as_bytes(),
+                    capacity,
+                };
+                let mut buf = [0; 16];
+                for line in &expected {
+                    assert_eq!(
+                        reader.read_line_with(&mut buf, nlset),
+                        Ok(Some((line.content(), line.newline()))),
+                        "capacity={capacity}"
+                    );
+                }
+                assert_eq!(reader.read_line_with(&mut buf, nlset), Ok(None));
+            }
+        }
+    }
+
+    #[test]
+    fn cr_held_back() {
+        let mut reader = ChunkReader {
+            data: b"foo\r\nbar",
+            capacity: 4,
+        };
+        let mut buf = [0; 8];
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::ASCII),
+            Ok(Some(("foo", Some(Newline::CrLf))))
+        );
+        assert_eq!(reader.fill_buf(), Ok(&b"bar"[..]));
+    }
+
+    #[test]
+    fn buffer_full() {
+        let mut reader = "1234\u{2028}12345\u{2028}ok".as_bytes();
+        let mut buf = [0; 4];
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::UNICODE),
+            Ok(Some(("1234", Some(Newline::LineSeparator))))
+        );
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::UNICODE),
+            Err(ReadLineError::BufferFull)
+        );
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::UNICODE),
+            Ok(Some(("ok", None)))
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut reader = &b"foo\xFF\nbar\n"[..];
+        let mut buf = [0; 8];
+        assert!(matches!(
+            reader.read_line_with(&mut buf, NewlineSet::ASCII),
+            Err(ReadLineError::InvalidUtf8(_))
+        ));
+        assert_eq!(
+            reader.read_line_with(&mut buf, NewlineSet::ASCII),
+            Ok(Some(("bar", Some(Newline::LineFeed))))
+        );
+    }
+
+    read_lines_cases! {
+        fn read_lines_into(#[case] s: &str, #[case] nlset: NewlineSet) {
+            let mut reader = s.as_bytes();
+            let mut asm = LineAssembler::<16>::new(nlset);
+            for line in nlset.lines_with_newlines(s) {
+                assert_eq!(
+                    reader.read_line_into(&mut asm),
+                    Ok(Some((line.content(), line.newline())))
+                );
+            }
+            assert_eq!(reader.read_line_into(&mut asm), Ok(None));
+        }
+    }
+
+    #[test]
+    fn read_line_into_errors() {
+        let mut reader = &b"1234\r\n12345\r\nf\xFF\nok\n123456"[..];
+        let mut asm = LineAssembler::<4>::new(NewlineSet::ASCII);
+        assert_eq!(
+            reader.read_line_into(&mut asm),
+            Ok(Some(("1234", Some(Newline::CrLf))))
+        );
+        assert_eq!(
+            reader.read_line_into(&mut asm),
+            Err(ReadLineError::BufferFull)
+        );
+        assert!(matches!(
+            reader.read_line_into(&mut asm),
+            Err(ReadLineError::InvalidUtf8(_))
+        ));
+        assert_eq!(
+            reader.read_line_into(&mut asm),
+            Ok(Some(("ok", Some(Newline::LineFeed))))
+        );
+        assert_eq!(
+            reader.read_line_into(&mut asm),
+            Err(ReadLineError::BufferFull)
+        );
+        assert_eq!(reader.read_line_into(&mut asm), Ok(None));
+    }
+
+    #[test]
+    fn write_line() {
+        let mut buf = [0; 16];
+        let mut writer = &mut buf[..];
+        assert_eq!(writer.write_line("foo", Newline::LineSeparator), Ok(()));
+        assert_eq!(writer.write_line("", Newline::CrLf), Ok(()));
+        assert_eq!(writer.len(), 8);
+        assert_eq!(&buf[..8], "foo\u{2028}\r\n".as_bytes());
+    }
+}
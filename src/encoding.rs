@@ -0,0 +1,498 @@
+use crate::bytes::{rsearch_bytes, search_bytes};
+use crate::errors::UnencodableNewlineError;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use crate::pattern::NewlinePattern;
+use core::fmt;
+#[cfg(feature = "std")]
+use {
+    crate::normalize::{normalize_bytes_in_place_with, normalize_bytes_with},
+    std::borrow::Cow,
+};
+
+/// An encoding of text as bytes, used to determine which byte sequences
+/// represent which newline sequences.
+///
+/// All of the newline sequences that are representable in an encoding other
+/// than UTF-8 are encoded as single bytes (or, for [`Newline::CrLf`], as a
+/// pair of bytes).
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ByteEncoding {
+    /// UTF-8, in which every [`Newline`] can be represented.  This is the
+    /// encoding assumed by [`NewlinePattern::search_in()`] when searching a
+    /// [`Newline`] or [`NewlineSet`] directly.
+    Utf8,
+
+    /// ISO-8859-1 (Latin-1), in which [`Newline::NextLine`] is the single byte
+    /// 0x85.  [`Newline::LineSeparator`] and [`Newline::ParagraphSeparator`]
+    /// cannot be represented in this encoding.
+    ///
+    /// This encoding can also be used for 8-bit encodings that agree with
+    /// Latin-1 on the bytes 0x0A through 0x0D, such as Windows-1252.  Note,
+    /// however, that in Windows-1252, 0x85 is "…" (U+2026 HORIZONTAL
+    /// ELLIPSIS), so [`Newline::NextLine`] should be omitted from the
+    /// [`NewlineSet`] when processing Windows-1252 text.
+    Latin1,
+
+    /// EBCDIC, as used by IBM mainframes.  In this encoding,
+    /// [`Newline::NextLine`] is the byte 0x15 (NL), [`Newline::LineFeed`] is
+    /// 0x25, [`Newline::CarriageReturn`] is 0x0D, [`Newline::CrLf`] is the
+    /// pair 0x0D 0x25, [`Newline::VerticalTab`] is 0x0B, and
+    /// [`Newline::FormFeed`] is 0x0C.  [`Newline::LineSeparator`] and
+    /// [`Newline::ParagraphSeparator`] cannot be represented in this encoding.
+    ///
+    /// These control characters are shared by the common EBCDIC code pages,
+    /// including CP037, CP500, and CP1047, so this encoding can be used for
+    /// any of them.
+    Ebcdic,
+}
+
+impl ByteEncoding {
+    /// Returns the encoding of the newline sequence `nl`, or `None` if `nl`
+    /// cannot be represented in this encoding
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{ByteEncoding, Newline};
+    ///
+    /// assert_eq!(ByteEncoding::Utf8.encode(Newline::NextLine), Some(&b"\xC2\x85"[..]));
+    /// assert_eq!(ByteEncoding::Latin1.encode(Newline::NextLine), Some(&b"\x85"[..]));
+    /// assert_eq!(ByteEncoding::Latin1.encode(Newline::CrLf), Some(&b"\r\n"[..]));
+    /// assert_eq!(ByteEncoding::Latin1.encode(Newline::LineSeparator), None);
+    /// assert_eq!(ByteEncoding::Ebcdic.encode(Newline::CrLf), Some(&b"\x0D\x25"[..]));
+    /// ```
+    pub fn encode(&self, nl: Newline) -> Option<&'static [u8]> {
+        match (self, nl) {
+            (ByteEncoding::Utf8, nl) => Some(nl.as_str().as_bytes()),
+            (ByteEncoding::Latin1, Newline::NextLine) => Some(b"\x85"),
+            (ByteEncoding::Latin1, Newline::LineSeparator | Newline::ParagraphSeparator) => None,
+            (ByteEncoding::Latin1, nl) => Some(nl.as_str().as_bytes()),
+            (ByteEncoding::Ebcdic, Newline::LineFeed) => Some(b"\x25"),
+            (ByteEncoding::Ebcdic, Newline::VerticalTab) => Some(b"\x0B"),
+            (ByteEncoding::Ebcdic, Newline::FormFeed) => Some(b"\x0C"),
+            (ByteEncoding::Ebcdic, Newline::CarriageReturn) => Some(b"\x0D"),
+            (ByteEncoding::Ebcdic, Newline::CrLf) => Some(b"\x0D\x25"),
+            (ByteEncoding::Ebcdic, Newline::NextLine) => Some(b"\x15"),
+            (ByteEncoding::Ebcdic, Newline::LineSeparator | Newline::ParagraphSeparator) => None,
+        }
+    }
+
+    /// Returns the set of newline sequences that can be represented in this
+    /// encoding
+    pub fn representable(&self) -> NewlineSet {
+        Newline::iter()
+            .filter(|&nl| self.encode(nl).is_some())
+            .collect()
+    }
+
+    /// Returns a pattern for searching byte slices in this encoding for the
+    /// newline sequences in `nlset`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `nlset` contains a newline sequence that cannot be
+    /// represented in this encoding.
+    pub fn newline_set(
+        &self,
+        nlset: NewlineSet,
+    ) -> Result<EncodedNewlineSet, UnencodableNewlineError> {
+        match (nlset - self.representable()).iter().next() {
+            Some(newline) => Err(UnencodableNewlineError {
+                newline,
+                encoding: *self,
+            }),
+            None => Ok(EncodedNewlineSet {
+                nlset,
+                encoding: *self,
+            }),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn encode_or_err(&self, nl: Newline) -> Result<&'static [u8], UnencodableNewlineError> {
+        self.encode(nl).ok_or(UnencodableNewlineError {
+            newline: nl,
+            encoding: *self,
+        })
+    }
+}
+
+impl fmt::Display for ByteEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ByteEncoding::Utf8 => f.write_str("UTF-8"),
+            ByteEncoding::Latin1 => f.write_str("Latin-1"),
+            ByteEncoding::Ebcdic => f.write_str("EBCDIC"),
+        }
+    }
+}
+
+/// A [`NewlineSet`] paired with the [`ByteEncoding`] in which its newline
+/// sequences are to be searched for in byte slices.
+///
+/// An `EncodedNewlineSet` is a [`NewlinePattern`], and so it can be passed to
+/// the methods of [`NewlineBytesExt`][crate::NewlineBytesExt] in order to
+/// search & split byte slices in the given encoding.  As `str` and `[u16]`
+/// values have already been decoded, the encoding is ignored when searching
+/// them, and the pattern behaves the same as the underlying `NewlineSet`.
+///
+/// An `EncodedNewlineSet` is acquired by calling
+/// [`ByteEncoding::newline_set()`], which ensures that every newline sequence
+/// in the set can be represented in the encoding.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{ByteEncoding, Newline, NewlineBytesExt, NewlineSet};
+///
+/// let latin1 = ByteEncoding::Latin1.newline_set(NewlineSet::NLF).unwrap();
+/// let s = b"caf\xE9\r\nna\xEFve\x85end";
+/// assert_eq!(
+///     s.lines_with(latin1).collect::<Vec<_>>(),
+///     [&b"caf\xE9"[..], b"na\xEFve", b"end"],
+/// );
+/// assert_eq!(s.rfind_newline(latin1), Some((11, Newline::NextLine)));
+/// assert!(ByteEncoding::Latin1.newline_set(NewlineSet::UNICODE).is_err());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EncodedNewlineSet {
+    nlset: NewlineSet,
+    encoding: ByteEncoding,
+}
+
+impl EncodedNewlineSet {
+    /// Returns an `EncodedNewlineSet` that searches for the UTF-8 encodings
+    /// of the newline sequences in `nlset`
+    pub(crate) fn utf8(nlset: NewlineSet) -> EncodedNewlineSet {
+        EncodedNewlineSet {
+            nlset,
+            encoding: ByteEncoding::Utf8,
+        }
+    }
+
+    /// Returns the set of newline sequences searched for
+    pub fn newlines(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns the encoding in which newline sequences are searched for
+    pub fn encoding(&self) -> ByteEncoding {
+        self.encoding
+    }
+
+    /// Replace every newline sequence in `s` that is in this set with the
+    /// newline sequence `to`, using this set's encoding for both.
+    ///
+    /// If `s` does not contain any newline sequences that need to be
+    /// replaced, it is returned as-is without allocating.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `to` cannot be represented in this set's encoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{ByteEncoding, Newline, NewlineSet};
+    ///
+    /// let latin1 = ByteEncoding::Latin1.newline_set(NewlineSet::NLF).unwrap();
+    /// assert_eq!(
+    ///     latin1.normalize(b"foo\x85bar\r\n", Newline::LineFeed).unwrap(),
+    ///     &b"foo\nbar\n"[..],
+    /// );
+    /// assert!(latin1.normalize(b"foo\n", Newline::LineSeparator).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn normalize<'a>(
+        &self,
+        s: &'a [u8],
+        to: Newline,
+    ) -> Result<Cow<'a, [u8]>, UnencodableNewlineError> {
+        let to_bytes = self.encoding.encode_or_err(to)?;
+        Ok(normalize_bytes_with(s, self, to_bytes))
+    }
+
+    /// Replace every newline sequence in `buf` that is in this set with the
+    /// newline sequence `to`, using this set's encoding for both, and
+    /// modifying `buf` in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `to` cannot be represented in this set's encoding,
+    /// in which case `buf` is left unmodified.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn normalize_in_place(
+        &self,
+        buf: &mut Vec<u8>,
+        to: Newline,
+    ) -> Result<(), UnencodableNewlineError> {
+        let to_bytes = self.encoding.encode_or_err(to)?;
+        let compacting = self
+            .nlset
+            .iter()
+            .all(|nl| self.encoding.encode(nl).map_or(0, <[u8]>::len) >= to_bytes.len());
+        normalize_bytes_in_place_with(buf, self, to_bytes, compacting);
+        Ok(())
+    }
+
+    /// If `s` starts with the encoding of a newline sequence in this set,
+    /// returns that newline sequence.  [`Newline::CrLf`] is preferred over
+    /// [`Newline::CarriageReturn`] when both match.
+    fn newline_prefix(&self, s: &[u8]) -> Option<Newline> {
+        if self.nlset.crlf && self.encoded_crlf().is_some_and(|crlf| s.starts_with(crlf)) {
+            return Some(Newline::CrLf);
+        }
+        self.nlset.iter().find(|&nl| {
+            nl != Newline::CrLf
+                && self
+                    .encoding
+                    .encode(nl)
+                    .is_some_and(|enc| s.starts_with(enc))
+        })
+    }
+
+    fn encoded_crlf(&self) -> Option<&'static [u8]> {
+        self.encoding.encode(Newline::CrLf)
+    }
+
+    /// Returns the start & end indices of the first newline sequence in `s`
+    /// that is in this set, searching for the encodings in this set's
+    /// encoding
+    pub(crate) fn search_bytes(&self, s: &[u8]) -> Option<(usize, usize)> {
+        if self.encoding == ByteEncoding::Utf8 {
+            return search_bytes(self.nlset, s);
+        }
+        if self.nlset.is_empty() {
+            return None;
+        }
+        (0..s.len()).find_map(|i| {
+            let nl = self.newline_prefix(&s[i..])?;
+            Some((i, i + self.encoding.encode(nl).map_or(0, <[u8]>::len)))
+        })
+    }
+
+    /// Returns the start & end indices of the last newline sequence in `s`
+    /// that is in this set, searching for the encodings in this set's
+    /// encoding
+    pub(crate) fn rsearch_bytes(&self, s: &[u8]) -> Option<(usize, usize)> {
+        if self.encoding == ByteEncoding::Utf8 {
+            return rsearch_bytes(self.nlset, s);
+        }
+        if self.nlset.is_empty() {
+            return None;
+        }
+        // In non-UTF-8 encodings, the CR LF pair is two bytes long, so a
+        // match ending at index `i` may start at `i - 1`.
+        (0..s.len()).rev().find_map(|i| {
+            if self.nlset.crlf
+                && i > 0
+                && self
+                    .encoded_crlf()
+                    .is_some_and(|crlf| s[(i - 1)..=i] == *crlf)
+            {
+                return Some((i - 1, i + 1));
+            }
+            let nl = self.newline_prefix(&s[i..])?;
+            Some((i, i + self.encoding.encode(nl).map_or(0, <[u8]>::len)))
+        })
+    }
+}
+
+impl NewlinePattern for EncodedNewlineSet {
+    fn search(&self, s: &str) -> Option<(usize, usize)> {
+        self.nlset.search(s)
+    }
+
+    fn rsearch(&self, s: &str) -> Option<(usize, usize)> {
+        self.nlset.rsearch(s)
+    }
+}
+
+const ENCODINGS: [ByteEncoding; 3] = [
+    ByteEncoding::Utf8,
+    ByteEncoding::Latin1,
+    ByteEncoding::Ebcdic,
+];
+
+/// Returns the `Newline` encoded by `s`, which must be a complete byte
+/// sequence matched by a [`NewlinePattern`] in some [`ByteEncoding`].
+///
+/// The byte sequences used for newlines by the supported encodings never
+/// conflict with each other (i.e., no sequence represents different newlines
+/// in different encodings), so the encoding need not be known.
+pub(crate) fn decode_newline(s: &[u8]) -> Option<Newline> {
+    ENCODINGS
+        .into_iter()
+        .find_map(|enc| Newline::iter().find(|&nl| enc.encode(nl) == Some(s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::NewlineBytesExt;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[test]
+    fn encodings_do_not_conflict() {
+        for enc1 in ENCODINGS {
+            for nl in Newline::iter() {
+                if let Some(s) = enc1.encode(nl) {
+                    assert_eq!(decode_newline(s), Some(nl));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn latin1_rejects_ls_ps() {
+        assert_eq!(
+            ByteEncoding::Latin1.newline_set(NewlineSet::UNICODE),
+            Err(UnencodableNewlineError {
+                newline: Newline::LineSeparator,
+                encoding: ByteEncoding::Latin1,
+            })
+        );
+        assert_eq!(
+            ByteEncoding::Latin1.representable(),
+            NewlineSet::UNICODE - Newline::LineSeparator - Newline::ParagraphSeparator
+        );
+        assert!(ByteEncoding::Utf8.newline_set(NewlineSet::ALL).is_ok());
+    }
+
+    #[rstest]
+    #[case(b"", vec![])]
+    #[case(b"foo\x85bar", vec![(&b"foo"[..], Some(Newline::NextLine)), (b"bar", None)])]
+    #[case(b"\xC2\x85\r\n\r", vec![(&b"\xC2"[..], Some(Newline::NextLine)), (b"", Some(Newline::CrLf)), (b"", Some(Newline::CarriageReturn))])]
+    #[case(b"\xE9\n\x0B\x0C", vec![(&b"\xE9"[..], Some(Newline::LineFeed)), (b"", Some(Newline::VerticalTab)), (b"", Some(Newline::FormFeed))])]
+    fn latin1_lines(#[case] s: &[u8], #[case] lines: Vec<(&[u8], Option<Newline>)>) {
+        let Ok(pattern) = ByteEncoding::Latin1.newline_set(ByteEncoding::Latin1.representable())
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            s.lines_with_newlines(pattern)
+                .map(|line| (line.content(), line.newline()))
+                .collect_vec(),
+            lines
+        );
+        assert_eq!(
+            s.lines_with_newlines(pattern)
+                .rev()
+                .map(|line| (line.content(), line.newline()))
+                .collect_vec(),
+            lines.into_iter().rev().collect_vec()
+        );
+    }
+
+    #[rstest]
+    #[case(b"", vec![])]
+    #[case(b"\xC8\x85\x93\x93\x96\x15\xE6\x96\x99\x93\x84", vec![(&b"\xC8\x85\x93\x93\x96"[..], Some(Newline::NextLine)), (b"\xE6\x96\x99\x93\x84", None)])]
+    #[case(b"\x81\x0D\x25\x0D\x25\x0D", vec![(&b"\x81"[..], Some(Newline::CrLf)), (b"", Some(Newline::CrLf)), (b"", Some(Newline::CarriageReturn))])]
+    #[case(b"\x0A\x25\x0B\x0C\x85", vec![(&b"\x0A"[..], Some(Newline::LineFeed)), (b"", Some(Newline::VerticalTab)), (b"", Some(Newline::FormFeed)), (b"\x85", None)])]
+    fn ebcdic_lines(#[case] s: &[u8], #[case] lines: Vec<(&[u8], Option<Newline>)>) {
+        let Ok(pattern) = ByteEncoding::Ebcdic.newline_set(ByteEncoding::Ebcdic.representable())
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            s.lines_with_newlines(pattern)
+                .map(|line| (line.content(), line.newline()))
+                .collect_vec(),
+            lines
+        );
+        assert_eq!(
+            s.lines_with_newlines(pattern)
+                .rev()
+                .map(|line| (line.content(), line.newline()))
+                .collect_vec(),
+            lines.into_iter().rev().collect_vec()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn ebcdic_normalize() {
+        let Ok(pattern) = ByteEncoding::Ebcdic.newline_set(NewlineSet::NLF) else {
+            unreachable!()
+        };
+        let Ok(result) = pattern.normalize(b"\xC1\x15\xC2\x0D\x25\xC3\x25", Newline::NextLine)
+        else {
+            unreachable!()
+        };
+        assert_eq!(&*result, b"\xC1\x15\xC2\x15\xC3\x15");
+        assert!(matches!(result, Cow::Owned(_)));
+        let Ok(result) = pattern.normalize(b"\xC1\x15\xC2\x15", Newline::NextLine) else {
+            unreachable!()
+        };
+        assert_eq!(&*result, b"\xC1\x15\xC2\x15");
+        assert!(matches!(result, Cow::Borrowed(_)));
+        let mut buf = b"\xC1\x15\xC2\x25".to_vec();
+        assert_eq!(pattern.normalize_in_place(&mut buf, Newline::CrLf), Ok(()));
+        assert_eq!(buf, b"\xC1\x0D\x25\xC2\x0D\x25");
+    }
+
+    #[test]
+    fn utf8_matches_newline_set() {
+        let Ok(pattern) = ByteEncoding::Utf8.newline_set(NewlineSet::UNICODE) else {
+            unreachable!()
+        };
+        let s = b"foo\xC2\x85bar\x85\xE2\x80\xA8";
+        assert_eq!(
+            s.lines_with(pattern).collect_vec(),
+            s.lines_with(NewlineSet::UNICODE).collect_vec()
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[rstest]
+    #[case(b"foo\x85bar\r\n", Newline::LineFeed, b"foo\nbar\n")]
+    #[case(b"foo\x85bar\r\n", Newline::CrLf, b"foo\r\nbar\r\n")]
+    #[case(b"foo\nbar\r", Newline::NextLine, b"foo\x85bar\x85")]
+    #[case(b"foo\n\r\n", Newline::CarriageReturn, b"foo\r\r")]
+    #[case(b"foo\nbar\n", Newline::LineFeed, b"foo\nbar\n")]
+    #[case(b"foo\x85\xE9", Newline::NextLine, b"foo\x85\xE9")]
+    #[case(b"", Newline::CrLf, b"")]
+    fn latin1_normalize(#[case] s: &[u8], #[case] to: Newline, #[case] expected: &[u8]) {
+        let Ok(pattern) = ByteEncoding::Latin1.newline_set(NewlineSet::NLF) else {
+            unreachable!()
+        };
+        let Ok(result) = pattern.normalize(s, to) else {
+            unreachable!()
+        };
+        assert_eq!(&*result, expected);
+        assert_eq!(matches!(result, Cow::Borrowed(_)), s == expected);
+        let mut buf = s.to_vec();
+        assert_eq!(pattern.normalize_in_place(&mut buf, to), Ok(()));
+        assert_eq!(buf, expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn latin1_normalize_unencodable() {
+        let Ok(pattern) = ByteEncoding::Latin1.newline_set(NewlineSet::NLF) else {
+            unreachable!()
+        };
+        let err = UnencodableNewlineError {
+            newline: Newline::ParagraphSeparator,
+            encoding: ByteEncoding::Latin1,
+        };
+        assert_eq!(
+            pattern.normalize(b"foo\n", Newline::ParagraphSeparator),
+            Err(err)
+        );
+        let mut buf = b"foo\n".to_vec();
+        assert_eq!(
+            pattern.normalize_in_place(&mut buf, Newline::ParagraphSeparator),
+            Err(err)
+        );
+        assert_eq!(buf, b"foo\n");
+        assert_eq!(
+            err.to_string(),
+            "ParagraphSeparator cannot be represented in the Latin-1 encoding"
+        );
+    }
+}
@@ -1,5 +1,9 @@
 //! Error types
+use crate::encoding::ByteEncoding;
+use crate::nl::Newline;
 use core::fmt;
+#[cfg(feature = "tokio-util")]
+use std::io;
 
 /// Error returned by `TryFrom<char> for Newline` when given a `char` that is
 /// not a recognized newline sequence
@@ -33,3 +37,171 @@ impl fmt::Display for TryFromStrError {
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 impl std::error::Error for TryFromStrError {}
+
+/// Error returned when a [`Newline`] cannot be represented in a
+/// [`ByteEncoding`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UnencodableNewlineError {
+    /// The newline sequence that could not be represented
+    pub newline: Newline,
+
+    /// The encoding in which the newline sequence could not be represented
+    pub encoding: ByteEncoding,
+}
+
+impl fmt::Display for UnencodableNewlineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} cannot be represented in the {} encoding",
+            self.newline, self.encoding
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for UnencodableNewlineError {}
+
+/// Error returned by [`LineAssembler`][crate::LineAssembler] when a line cannot
+/// be assembled
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LineAssemblerError {
+    /// The content of a line did not fit in the assembler's buffer.  The rest
+    /// of the line, up through its terminating newline sequence, is
+    /// discarded.
+    Overflow,
+
+    /// The content of a line was not valid UTF-8.  The line is discarded.
+    InvalidUtf8(core::str::Utf8Error),
+}
+
+impl fmt::Display for LineAssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineAssemblerError::Overflow => f.write_str("line exceeded buffer capacity"),
+            LineAssemblerError::InvalidUtf8(e) => write!(f, "line was not valid UTF-8: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for LineAssemblerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LineAssemblerError::Overflow => None,
+            LineAssemblerError::InvalidUtf8(e) => Some(e),
+        }
+    }
+}
+
+/// Error returned by the [`Decoder`][tokio_util::codec::Decoder] and
+/// [`Encoder`][tokio_util::codec::Encoder] implementations of
+/// [`NewlineCodec`][crate::NewlineCodec] and
+/// [`NewlineStringCodec`][crate::NewlineStringCodec]
+#[cfg(feature = "tokio-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+#[derive(Debug)]
+pub enum NewlineCodecError {
+    /// The content of a frame exceeded the codec's maximum frame length.  The
+    /// frame is discarded, and decoding resumes after the frame's terminating
+    /// newline sequence.
+    MaxFrameLengthExceeded,
+
+    /// An I/O error occurred, or a frame decoded by a `NewlineStringCodec`
+    /// was not valid UTF-8
+    Io(io::Error),
+}
+
+#[cfg(feature = "tokio-util")]
+impl fmt::Display for NewlineCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewlineCodecError::MaxFrameLengthExceeded => f.write_str("max frame length exceeded"),
+            NewlineCodecError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util")))]
+impl std::error::Error for NewlineCodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NewlineCodecError::MaxFrameLengthExceeded => None,
+            NewlineCodecError::Io(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl From<io::Error> for NewlineCodecError {
+    fn from(e: io::Error) -> NewlineCodecError {
+        NewlineCodecError::Io(e)
+    }
+}
+
+/// Error returned by the `read_line_with()` methods of
+/// [`EmbeddedBufReadNewlineExt`][crate::EmbeddedBufReadNewlineExt] and
+/// `EmbeddedAsyncBufReadNewlineExt` when a line cannot be read into the
+/// caller's buffer
+///
+/// The type parameter `E` is the error type of the underlying reader.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded-io")))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReadLineError<E> {
+    /// The content of the line did not fit in the buffer.  The rest of the
+    /// line, up through its terminating newline sequence, has been consumed
+    /// from the reader and discarded.
+    BufferFull,
+
+    /// The content of the line was not valid UTF-8.  The line has been
+    /// consumed from the reader and discarded.
+    InvalidUtf8(core::str::Utf8Error),
+
+    /// The underlying reader returned an error
+    Io(E),
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E: fmt::Display> fmt::Display for ReadLineError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadLineError::BufferFull => f.write_str("line exceeded buffer capacity"),
+            ReadLineError::InvalidUtf8(e) => write!(f, "line was not valid UTF-8: {e}"),
+            ReadLineError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+#[cfg(all(feature = "embedded-io", feature = "std"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "embedded-io", feature = "std"))))]
+impl<E: std::error::Error + 'static> std::error::Error for ReadLineError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadLineError::BufferFull => None,
+            ReadLineError::InvalidUtf8(e) => Some(e),
+            ReadLineError::Io(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E: embedded_io::Error> embedded_io::Error for ReadLineError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            ReadLineError::BufferFull => embedded_io::ErrorKind::OutOfMemory,
+            ReadLineError::InvalidUtf8(_) => embedded_io::ErrorKind::InvalidData,
+            ReadLineError::Io(e) => e.kind(),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<E> From<E> for ReadLineError<E> {
+    fn from(e: E) -> ReadLineError<E> {
+        ReadLineError::Io(e)
+    }
+}
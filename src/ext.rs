@@ -0,0 +1,639 @@
+use crate::haystack::Haystack;
+use crate::iter::{Lines, LinesInclusive, LinesWithNewlines};
+use crate::nl::Newline;
+use crate::pattern::{rsplit_once, split_once, NewlinePattern};
+
+mod private {
+    #[allow(unnameable_types)]
+    pub trait Sealed {}
+
+    impl Sealed for str {}
+
+    impl Sealed for [u8] {}
+
+    impl Sealed for [u16] {}
+}
+
+/// Extension trait for searching & splitting strings on the newline sequences
+/// matched by a [`NewlinePattern`] (i.e., a [`Newline`] or
+/// [`NewlineSet`][crate::NewlineSet])
+///
+/// This trait is implemented for [`str`], and so its methods are also
+/// available on `String` values.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineSet, NewlineStrExt};
+///
+/// let s = "foo\r\nbar\u{2028}baz\n";
+/// assert_eq!(
+///     s.lines_with(NewlineSet::UNICODE).collect::<Vec<_>>(),
+///     ["foo", "bar", "baz"],
+/// );
+/// assert_eq!(s.find_newline(NewlineSet::UNICODE), Some((3, Newline::CrLf)));
+/// assert!(s.ends_with_newline(Newline::LineFeed));
+/// ```
+pub trait NewlineStrExt: private::Sealed {
+    /// Returns an iterator over the lines of the string, split on the newline
+    /// sequences matched by `pattern`.  The lines do not include their
+    /// terminating newline sequences, and a newline at the end of the string
+    /// does not produce a trailing empty line.
+    ///
+    /// This is equivalent to [`NewlineSet::lines()`][crate::NewlineSet::lines]
+    /// or [`Newline::lines()`].
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P>;
+
+    /// Returns an iterator over the lines of the string, split on the newline
+    /// sequences matched by `pattern`.  The lines include their terminating
+    /// newline sequences, and a newline at the end of the string does not
+    /// produce a trailing empty line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{NewlineSet, NewlineStrExt};
+    ///
+    /// let mut iter = "foo\r\nbar\rbaz".split_lines_inclusive(NewlineSet::ASCII);
+    /// assert_eq!(iter.next(), Some("foo\r\n"));
+    /// assert_eq!(iter.next(), Some("bar\r"));
+    /// assert_eq!(iter.next(), Some("baz"));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P>;
+
+    /// Returns the byte index of the first newline sequence in the string
+    /// matched by `pattern`, along with the [`Newline`] that was matched
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// Returns the byte index of the last newline sequence in the string
+    /// matched by `pattern`, along with the [`Newline`] that was matched
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// Returns `true` if the string ends with a newline sequence matched by
+    /// `pattern`
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool;
+
+    /// If a newline sequence matched by `pattern` starts at byte index `idx`
+    /// of the string, returns the corresponding [`Newline`].  Returns `None`
+    /// if there is no such newline or if `idx` is not on a `char` boundary.
+    ///
+    /// Only the text at & after `idx` is examined, so if `idx` points to the
+    /// LF of a CR LF pair, the result will be [`Newline::LineFeed`] (if
+    /// matched by `pattern`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet, NewlineStrExt};
+    ///
+    /// let s = "foo\r\nbar";
+    /// assert_eq!(s.newline_at(3, NewlineSet::ASCII), Some(Newline::CrLf));
+    /// assert_eq!(s.newline_at(3, Newline::CarriageReturn), Some(Newline::CarriageReturn));
+    /// assert_eq!(s.newline_at(2, NewlineSet::ASCII), None);
+    /// ```
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline>;
+}
+
+impl NewlineStrExt for str {
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P> {
+        Lines::new(pattern, self)
+    }
+
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P> {
+        LinesInclusive::new(pattern, self)
+    }
+
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        find_newline(self, &pattern)
+    }
+
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        rfind_newline(self, &pattern)
+    }
+
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool {
+        ends_with_newline(self, &pattern)
+    }
+
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline> {
+        newline_at(self, idx, &pattern)
+    }
+}
+
+/// Extension trait for searching & splitting byte slices on the UTF-8
+/// encodings of the newline sequences matched by a [`NewlinePattern`]
+///
+/// The byte slices need not be valid UTF-8; invalid bytes are treated as
+/// ordinary non-newline content.  This trait is implemented for `[u8]`, and so
+/// its methods are also available on `Vec<u8>` values.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineBytesExt, NewlineSet};
+///
+/// let s = b"foo\r\n\xFFbar\xE2\x80\xA8baz\n";
+/// assert_eq!(
+///     s.lines_with(NewlineSet::UNICODE).collect::<Vec<_>>(),
+///     [&b"foo"[..], b"\xFFbar", b"baz"],
+/// );
+/// assert_eq!(s.find_newline(NewlineSet::UNICODE), Some((3, Newline::CrLf)));
+/// assert_eq!(
+///     s.rsplit_once_newline(NewlineSet::UNICODE),
+///     Some((&b"foo\r\n\xFFbar\xE2\x80\xA8baz"[..], Newline::LineFeed, &b""[..])),
+/// );
+/// ```
+pub trait NewlineBytesExt: private::Sealed {
+    /// Returns an iterator over the lines of the byte slice, split on the
+    /// newline sequences matched by `pattern`.  The lines do not include their
+    /// terminating newline sequences, and a newline at the end of the byte
+    /// slice does not produce a trailing empty line.
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, [u8]>;
+
+    /// Returns an iterator over the lines of the byte slice, split on the
+    /// newline sequences matched by `pattern`, with each line yielded as a
+    /// [`Line`][crate::Line] that records the [`Newline`] that terminated it
+    /// and the byte offsets of the line's parts.
+    fn lines_with_newlines<P: NewlinePattern>(&self, pattern: P) -> LinesWithNewlines<'_, P, [u8]>;
+
+    /// Returns an iterator over the lines of the byte slice, split on the
+    /// newline sequences matched by `pattern`.  The lines include their
+    /// terminating newline sequences, and a newline at the end of the byte
+    /// slice does not produce a trailing empty line.
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, [u8]>;
+
+    /// Splits the byte slice on the first newline sequence matched by
+    /// `pattern`, returning the part before the newline, the [`Newline`]
+    /// itself, and the part after the newline
+    fn split_once_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(&[u8], Newline, &[u8])>;
+
+    /// Splits the byte slice on the last newline sequence matched by
+    /// `pattern`, returning the part before the newline, the [`Newline`]
+    /// itself, and the part after the newline
+    fn rsplit_once_newline<P: NewlinePattern>(&self, pattern: P)
+        -> Option<(&[u8], Newline, &[u8])>;
+
+    /// Returns the index of the first newline sequence in the byte slice
+    /// matched by `pattern`, along with the [`Newline`] that was matched
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// Returns the index of the last newline sequence in the byte slice
+    /// matched by `pattern`, along with the [`Newline`] that was matched
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// Returns `true` if the byte slice ends with a newline sequence matched
+    /// by `pattern`
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool;
+
+    /// If a newline sequence matched by `pattern` starts at index `idx` of the
+    /// byte slice, returns the corresponding [`Newline`].  Returns `None` if
+    /// there is no such newline or if `idx` is out of bounds.
+    ///
+    /// Only the bytes at & after `idx` are examined, so if `idx` points to
+    /// the LF of a CR LF pair, the result will be [`Newline::LineFeed`] (if
+    /// matched by `pattern`).
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline>;
+}
+
+impl NewlineBytesExt for [u8] {
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, [u8]> {
+        Lines::new(pattern, self)
+    }
+
+    fn lines_with_newlines<P: NewlinePattern>(&self, pattern: P) -> LinesWithNewlines<'_, P, [u8]> {
+        LinesWithNewlines::new(pattern, self)
+    }
+
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, [u8]> {
+        LinesInclusive::new(pattern, self)
+    }
+
+    fn split_once_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(&[u8], Newline, &[u8])> {
+        split_once(&pattern, self)
+    }
+
+    fn rsplit_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&[u8], Newline, &[u8])> {
+        rsplit_once(&pattern, self)
+    }
+
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        find_newline(self, &pattern)
+    }
+
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        rfind_newline(self, &pattern)
+    }
+
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool {
+        ends_with_newline(self, &pattern)
+    }
+
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline> {
+        newline_at(self, idx, &pattern)
+    }
+}
+
+/// Extension trait for searching & splitting slices of UTF-16 code units on
+/// the UTF-16 encodings of the newline sequences matched by a
+/// [`NewlinePattern`]
+///
+/// The slices need not be valid UTF-16; unpaired surrogates are treated as
+/// ordinary non-newline content.  All indices are measured in code units.
+/// This trait is implemented for `[u16]`, and so its methods are also
+/// available on `Vec<u16>` values.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineSet, NewlineUtf16Ext};
+///
+/// let s: Vec<u16> = "foo\r\nbar\u{2028}baz".encode_utf16().collect();
+/// let lines = s
+///     .lines_with(NewlineSet::UNICODE)
+///     .map(String::from_utf16_lossy)
+///     .collect::<Vec<_>>();
+/// assert_eq!(lines, ["foo", "bar", "baz"]);
+/// assert_eq!(s.rfind_newline(NewlineSet::UNICODE), Some((8, Newline::LineSeparator)));
+/// ```
+pub trait NewlineUtf16Ext: private::Sealed {
+    /// The UTF-16 counterpart of [`NewlineBytesExt::lines_with()`]
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, [u16]>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::lines_with_newlines()`]
+    fn lines_with_newlines<P: NewlinePattern>(&self, pattern: P)
+        -> LinesWithNewlines<'_, P, [u16]>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::split_lines_inclusive()`]
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, [u16]>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::split_once_newline()`]
+    fn split_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&[u16], Newline, &[u16])>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::rsplit_once_newline()`]
+    fn rsplit_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&[u16], Newline, &[u16])>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::find_newline()`]
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::rfind_newline()`]
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)>;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::ends_with_newline()`]
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool;
+
+    /// The UTF-16 counterpart of [`NewlineBytesExt::newline_at()`]
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline>;
+}
+
+impl NewlineUtf16Ext for [u16] {
+    fn lines_with<P: NewlinePattern>(&self, pattern: P) -> Lines<'_, P, [u16]> {
+        Lines::new(pattern, self)
+    }
+
+    fn lines_with_newlines<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> LinesWithNewlines<'_, P, [u16]> {
+        LinesWithNewlines::new(pattern, self)
+    }
+
+    fn split_lines_inclusive<P: NewlinePattern>(&self, pattern: P) -> LinesInclusive<'_, P, [u16]> {
+        LinesInclusive::new(pattern, self)
+    }
+
+    fn split_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&[u16], Newline, &[u16])> {
+        split_once(&pattern, self)
+    }
+
+    fn rsplit_once_newline<P: NewlinePattern>(
+        &self,
+        pattern: P,
+    ) -> Option<(&[u16], Newline, &[u16])> {
+        rsplit_once(&pattern, self)
+    }
+
+    fn find_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        find_newline(self, &pattern)
+    }
+
+    fn rfind_newline<P: NewlinePattern>(&self, pattern: P) -> Option<(usize, Newline)> {
+        rfind_newline(self, &pattern)
+    }
+
+    fn ends_with_newline<P: NewlinePattern>(&self, pattern: P) -> bool {
+        ends_with_newline(self, &pattern)
+    }
+
+    fn newline_at<P: NewlinePattern>(&self, idx: usize, pattern: P) -> Option<Newline> {
+        newline_at(self, idx, &pattern)
+    }
+}
+
+// The trait methods above are thin wrappers around the following functions,
+// which implement each operation once for every `Haystack`.
+
+fn find_newline<H: Haystack + ?Sized, P: NewlinePattern>(
+    s: &H,
+    pattern: &P,
+) -> Option<(usize, Newline)> {
+    let (start, end) = s.search_pattern(pattern)?;
+    Some((start, s.matched_newline(start, end)))
+}
+
+fn rfind_newline<H: Haystack + ?Sized, P: NewlinePattern>(
+    s: &H,
+    pattern: &P,
+) -> Option<(usize, Newline)> {
+    let (start, end) = s.rsearch_pattern(pattern)?;
+    Some((start, s.matched_newline(start, end)))
+}
+
+fn ends_with_newline<H: Haystack + ?Sized, P: NewlinePattern>(s: &H, pattern: &P) -> bool {
+    let suffix = s.newline_window_end();
+    suffix
+        .rsearch_pattern(pattern)
+        .is_some_and(|(_, end)| end == suffix.haystack_len())
+}
+
+fn newline_at<H: Haystack + ?Sized, P: NewlinePattern>(
+    s: &H,
+    idx: usize,
+    pattern: &P,
+) -> Option<Newline> {
+    // Only the text at & after `idx` is examined, and only as much of it as
+    // could hold a single newline sequence.
+    let prefix = s.newline_window_at(idx)?;
+    match prefix.search_pattern(pattern)? {
+        (0, end) => Some(prefix.matched_newline(0, end)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlset::NewlineSet;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("foo", vec!["foo"])]
+    #[case("foo\r\n", vec!["foo\r\n"])]
+    #[case("foo\r\n\rbar\n\nbaz", vec!["foo\r\n", "\r", "bar\n", "\n", "baz"])]
+    fn split_lines_inclusive(#[case] s: &str, #[case] mut lines: Vec<&str>) {
+        assert_eq!(
+            s.split_lines_inclusive(NewlineSet::ASCII).collect_vec(),
+            lines
+        );
+        lines.reverse();
+        assert_eq!(
+            s.split_lines_inclusive(NewlineSet::ASCII)
+                .rev()
+                .collect_vec(),
+            lines
+        );
+    }
+
+    #[rstest]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", Some((3, Newline::CrLf)), Some((3, Newline::CrLf)))]
+    #[case(NewlineSet::RUST, "foo\rbar\nbaz\r", Some((7, Newline::LineFeed)), Some((7, Newline::LineFeed)))]
+    #[case(NewlineSet::UNICODE, "\u{2028}foo\u{0085}", Some((0, Newline::LineSeparator)), Some((6, Newline::NextLine)))]
+    #[case(NewlineSet::UNICODE, "foo", None, None)]
+    fn find_newline(
+        #[case] nlset: NewlineSet,
+        #[case] s: &str,
+        #[case] first: Option<(usize, Newline)>,
+        #[case] last: Option<(usize, Newline)>,
+    ) {
+        assert_eq!(s.find_newline(nlset), first);
+        assert_eq!(s.rfind_newline(nlset), last);
+    }
+
+    #[rstest]
+    #[case(NewlineSet::ASCII, "", false)]
+    #[case(NewlineSet::ASCII, "foo", false)]
+    #[case(NewlineSet::ASCII, "foo\n", true)]
+    #[case(NewlineSet::ASCII, "\r", true)]
+    #[case(NewlineSet::ASCII, "foo\r\n", true)]
+    #[case(NewlineSet::RUST, "foo\r", false)]
+    #[case(Newline::CarriageReturn.into(), "foo\r\n", false)]
+    #[case(Newline::LineFeed.into(), "foo\r\n", true)]
+    #[case(NewlineSet::UNICODE, "foo\u{2029}", true)]
+    #[case(NewlineSet::ASCII, "foo\u{2029}", false)]
+    #[case(NewlineSet::ASCII, "foo\n\u{2029}", false)]
+    fn ends_with_newline(#[case] nlset: NewlineSet, #[case] s: &str, #[case] r: bool) {
+        assert_eq!(s.ends_with_newline(nlset), r);
+    }
+
+    #[rstest]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", 3, Some(Newline::CrLf))]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", 4, Some(Newline::LineFeed))]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", 5, None)]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", 9, None)]
+    #[case(NewlineSet::ASCII, "foo\r\nbar", 10, None)]
+    #[case(NewlineSet::RUST, "foo\r\rbar", 3, None)]
+    #[case(NewlineSet::UNICODE, "foo\u{2028}", 3, Some(Newline::LineSeparator))]
+    #[case(NewlineSet::UNICODE, "foo\u{2028}", 4, None)]
+    #[case(Newline::CrLf.into(), "\r\r\n", 0, None)]
+    #[case(Newline::CrLf.into(), "\r\r\n", 1, Some(Newline::CrLf))]
+    fn newline_at(
+        #[case] nlset: NewlineSet,
+        #[case] s: &str,
+        #[case] idx: usize,
+        #[case] r: Option<Newline>,
+    ) {
+        assert_eq!(s.newline_at(idx, nlset), r);
+    }
+
+    mod bytes {
+        use super::*;
+
+        const SAMPLES: [&str; 5] = [
+            "",
+            "foo\r\nbar",
+            "foo\rbar\nbaz\r",
+            "\u{2028}foo\u{0085}\r\r\n",
+            "\r\n\n\u{2029}\r",
+        ];
+
+        #[rstest]
+        fn matches_str(
+            #[values(NewlineSet::ASCII, NewlineSet::RUST, NewlineSet::UNICODE, Newline::CarriageReturn.into(), Newline::CrLf | Newline::LineSeparator)]
+            nlset: NewlineSet,
+            #[values(0, 1, 2, 3, 4)] sample: usize,
+        ) {
+            let s = SAMPLES[sample];
+            let b = s.as_bytes();
+            assert_eq!(
+                b.lines_with(nlset).collect_vec(),
+                s.lines_with(nlset).map(str::as_bytes).collect_vec()
+            );
+            assert_eq!(
+                b.lines_with(nlset).rev().collect_vec(),
+                s.lines_with(nlset).rev().map(str::as_bytes).collect_vec()
+            );
+            assert_eq!(
+                b.split_lines_inclusive(nlset).collect_vec(),
+                s.split_lines_inclusive(nlset)
+                    .map(str::as_bytes)
+                    .collect_vec()
+            );
+            assert_eq!(
+                b.lines_with_newlines(nlset)
+                    .map(|line| (line.as_bytes(), line.newline(), line.span()))
+                    .collect_vec(),
+                nlset
+                    .lines_with_newlines(s)
+                    .map(|line| (line.as_str().as_bytes(), line.newline(), line.span()))
+                    .collect_vec()
+            );
+            assert_eq!(b.find_newline(nlset), s.find_newline(nlset));
+            assert_eq!(b.rfind_newline(nlset), s.rfind_newline(nlset));
+            assert_eq!(
+                b.split_once_newline(nlset),
+                nlset
+                    .split_once(s)
+                    .map(|(a, nl, b)| (a.as_bytes(), nl, b.as_bytes()))
+            );
+            assert_eq!(
+                b.rsplit_once_newline(nlset),
+                nlset
+                    .rsplit_once(s)
+                    .map(|(a, nl, b)| (a.as_bytes(), nl, b.as_bytes()))
+            );
+            assert_eq!(b.ends_with_newline(nlset), s.ends_with_newline(nlset));
+            for (i, _) in s.char_indices() {
+                assert_eq!(b.newline_at(i, nlset), s.newline_at(i, nlset));
+            }
+        }
+
+        #[rstest]
+        #[case(b"", vec![])]
+        #[case(b"\xFF\n\xE2\x80\n\xC2", vec![&b"\xFF"[..], b"\xE2\x80", b"\xC2"])]
+        #[case(b"\x85\xE2\x80\xA8\xC2\xC2\x85\xA8", vec![&b"\x85"[..], b"\xC2", b"\xA8"])]
+        #[case(b"\xE2\x80\r\n\xA8\r", vec![&b"\xE2\x80"[..], b"\xA8"])]
+        fn invalid_utf8(#[case] s: &[u8], #[case] mut lines: Vec<&[u8]>) {
+            assert_eq!(s.lines_with(NewlineSet::UNICODE).collect_vec(), lines);
+            lines.reverse();
+            assert_eq!(s.lines_with(NewlineSet::UNICODE).rev().collect_vec(), lines);
+        }
+
+        #[rstest]
+        #[case(Newline::CarriageReturn, b"\xFF\r\n", Some((1, 2)), Some((1, 2)))]
+        #[case(Newline::CrLf, b"\r\r\n\xFF\r\n", Some((1, 3)), Some((4, 6)))]
+        #[case(Newline::NextLine, b"\x85\xC2\x85", Some((1, 3)), Some((1, 3)))]
+        #[case(Newline::LineFeed, b"\xFF", None, None)]
+        fn newline_search_bytes(
+            #[case] nl: Newline,
+            #[case] s: &[u8],
+            #[case] first: Option<(usize, usize)>,
+            #[case] last: Option<(usize, usize)>,
+        ) {
+            assert_eq!(nl.search_in(s), first);
+            assert_eq!(nl.rsearch_in(s), last);
+        }
+    }
+
+    mod utf16 {
+        use super::*;
+
+        fn encode(s: &str) -> Vec<u16> {
+            s.encode_utf16().collect()
+        }
+
+        #[rstest]
+        fn matches_str(
+            #[values(NewlineSet::ASCII, NewlineSet::RUST, NewlineSet::UNICODE, Newline::CarriageReturn.into(), Newline::CrLf | Newline::LineSeparator)]
+            nlset: NewlineSet,
+            #[values(
+                "",
+                "foo\r\nbar",
+                "foo\rbar\nbaz\r",
+                "\u{2028}foo\u{0085}\r\r\n",
+                "\r\n\n\u{2029}\r"
+            )]
+            s: &str,
+        ) {
+            let u = encode(s);
+            assert_eq!(
+                u.lines_with(nlset).collect_vec(),
+                s.lines_with(nlset).map(encode).collect_vec()
+            );
+            assert_eq!(
+                u.lines_with(nlset).rev().collect_vec(),
+                s.lines_with(nlset).rev().map(encode).collect_vec()
+            );
+            assert_eq!(
+                u.split_lines_inclusive(nlset).collect_vec(),
+                s.split_lines_inclusive(nlset).map(encode).collect_vec()
+            );
+            assert_eq!(
+                u.lines_with_newlines(nlset)
+                    .map(|line| (line.as_utf16().to_vec(), line.newline()))
+                    .collect_vec(),
+                nlset
+                    .lines_with_newlines(s)
+                    .map(|line| (encode(line.as_str()), line.newline()))
+                    .collect_vec()
+            );
+            assert_eq!(
+                u.split_once_newline(nlset)
+                    .map(|(a, nl, b)| (a.to_vec(), nl, b.to_vec())),
+                nlset
+                    .split_once(s)
+                    .map(|(a, nl, b)| (encode(a), nl, encode(b)))
+            );
+            assert_eq!(
+                u.rsplit_once_newline(nlset)
+                    .map(|(a, nl, b)| (a.to_vec(), nl, b.to_vec())),
+                nlset
+                    .rsplit_once(s)
+                    .map(|(a, nl, b)| (encode(a), nl, encode(b)))
+            );
+            assert_eq!(
+                u.find_newline(nlset).map(|(_, nl)| nl),
+                s.find_newline(nlset).map(|(_, nl)| nl)
+            );
+            assert_eq!(
+                u.rfind_newline(nlset).map(|(_, nl)| nl),
+                s.rfind_newline(nlset).map(|(_, nl)| nl)
+            );
+            assert_eq!(u.ends_with_newline(nlset), s.ends_with_newline(nlset));
+        }
+
+        #[test]
+        fn unpaired_surrogates() {
+            let s: [u16; 8] = [
+                0xD800, 0x000A, 0xDC00, 0x000D, 0x000A, 0xD83D, 0x2028, 0x0061,
+            ];
+            let lines = s.lines_with_newlines(NewlineSet::UNICODE).collect_vec();
+            assert_eq!(lines.len(), 4);
+            assert_eq!(lines[0].content(), [0xD800]);
+            assert_eq!(lines[0].newline(), Some(Newline::LineFeed));
+            assert_eq!(lines[1].content(), [0xDC00]);
+            assert_eq!(lines[1].newline(), Some(Newline::CrLf));
+            assert_eq!(lines[1].newline_span(), Some((3, 5)));
+            assert_eq!(lines[2].content(), [0xD83D]);
+            assert_eq!(lines[2].newline(), Some(Newline::LineSeparator));
+            assert_eq!(lines[3].content(), [0x0061]);
+            assert_eq!(lines[3].newline(), None);
+            assert_eq!(s.newline_at(3, NewlineSet::ASCII), Some(Newline::CrLf));
+            assert_eq!(s.newline_at(4, NewlineSet::ASCII), Some(Newline::LineFeed));
+            assert_eq!(s.newline_at(5, NewlineSet::ASCII), None);
+        }
+    }
+}
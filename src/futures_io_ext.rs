@@ -0,0 +1,9 @@
+crate::async_bufread::async_bufread_ext! {
+    feature: "futures-io",
+    reader: "futures_io::AsyncBufRead",
+    trait: futures_io::AsyncBufRead,
+    ext: FuturesAsyncBufReadNewlineExt,
+    read_line_with: FuturesReadLineWith,
+    lines: FuturesLines,
+    buf_reader: futures_util::io::BufReader,
+}
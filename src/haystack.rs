@@ -0,0 +1,168 @@
+use crate::encoding::decode_newline;
+use crate::nl::Newline;
+use crate::pattern::NewlinePattern;
+use crate::utf16::{rsearch_utf16, search_utf16};
+
+pub(crate) mod private {
+    use crate::nl::Newline;
+    use crate::pattern::NewlinePattern;
+
+    /// The operations needed to split a [`Haystack`][super::Haystack] into
+    /// lines.  All offsets are measured in the haystack's units (bytes for
+    /// `str` and `[u8]`, code units for `[u16]`).
+    #[allow(unnameable_types)]
+    pub trait HaystackImpl {
+        fn haystack_len(&self) -> usize;
+
+        fn slice(&self, start: usize, end: usize) -> &Self;
+
+        fn search_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P)
+            -> Option<(usize, usize)>;
+
+        fn rsearch_pattern<P: NewlinePattern + ?Sized>(
+            &self,
+            pattern: &P,
+        ) -> Option<(usize, usize)>;
+
+        /// Returns the `Newline` for a newline sequence matched at
+        /// `self[start..end]` by a [`NewlinePattern`]
+        fn matched_newline(&self, start: usize, end: usize) -> Newline;
+
+        /// Returns the shortest suffix of `self` that is long enough to
+        /// contain any newline sequence
+        fn newline_window_end(&self) -> &Self;
+
+        /// Returns the shortest prefix of `self[idx..]` that is long enough
+        /// to contain any newline sequence, or `None` if `idx` is out of
+        /// bounds or (for `str`) not on a `char` boundary
+        fn newline_window_at(&self, idx: usize) -> Option<&Self>;
+    }
+}
+
+/// Trait for the types of text that can be split into lines by the iterators
+/// in this crate.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.  It
+/// is implemented for [`str`] and for `[u8]`; the latter is treated as
+/// UTF-8-encoded text that may contain invalid UTF-8, with newline sequences
+/// located by searching for their UTF-8 encodings (or, when the pattern is an
+/// [`EncodedNewlineSet`][crate::EncodedNewlineSet], their encodings in the
+/// pattern's [`ByteEncoding`][crate::ByteEncoding]).  When the `bstr` feature
+/// is enabled, it is also implemented for `bstr::BStr`, which is treated the
+/// same way as `[u8]`.
+///
+/// It is also implemented for `[u16]`, which is treated as UTF-16-encoded text
+/// that may contain unpaired surrogates, with newline sequences located by
+/// searching for their UTF-16 encodings.  Offsets into a `[u16]` are measured
+/// in code units.
+pub trait Haystack: private::HaystackImpl {}
+
+impl Haystack for str {}
+
+impl private::HaystackImpl for str {
+    fn haystack_len(&self) -> usize {
+        self.len()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &str {
+        &self[start..end]
+    }
+
+    fn search_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        pattern.search(self)
+    }
+
+    fn rsearch_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        pattern.rsearch(self)
+    }
+
+    fn matched_newline(&self, start: usize, end: usize) -> Newline {
+        crate::pattern::matched_newline(self, start, end)
+    }
+
+    fn newline_window_end(&self) -> &str {
+        // No newline sequence is more than two characters long.
+        let start = self.char_indices().nth_back(1).map_or(0, |(i, _)| i);
+        &self[start..]
+    }
+
+    fn newline_window_at(&self, idx: usize) -> Option<&str> {
+        let rest = self.get(idx..)?;
+        let end = rest.char_indices().nth(2).map_or(rest.len(), |(i, _)| i);
+        Some(&rest[..end])
+    }
+}
+
+impl Haystack for [u8] {}
+
+impl private::HaystackImpl for [u8] {
+    fn haystack_len(&self) -> usize {
+        self.len()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &[u8] {
+        &self[start..end]
+    }
+
+    fn search_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        pattern.encoded().search_bytes(self)
+    }
+
+    fn rsearch_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        pattern.encoded().rsearch_bytes(self)
+    }
+
+    fn matched_newline(&self, start: usize, end: usize) -> Newline {
+        let Some(nl) = decode_newline(&self[start..end]) else {
+            unreachable!("NewlinePattern should only match newline sequences");
+        };
+        nl
+    }
+
+    fn newline_window_end(&self) -> &[u8] {
+        // No newline sequence is more than three bytes long.
+        &self[self.len().saturating_sub(3)..]
+    }
+
+    fn newline_window_at(&self, idx: usize) -> Option<&[u8]> {
+        let rest = self.get(idx..)?;
+        Some(&rest[..rest.len().min(3)])
+    }
+}
+
+impl Haystack for [u16] {}
+
+impl private::HaystackImpl for [u16] {
+    fn haystack_len(&self) -> usize {
+        self.len()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &[u16] {
+        &self[start..end]
+    }
+
+    fn search_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        search_utf16(pattern.encoded().newlines(), self)
+    }
+
+    fn rsearch_pattern<P: NewlinePattern + ?Sized>(&self, pattern: &P) -> Option<(usize, usize)> {
+        rsearch_utf16(pattern.encoded().newlines(), self)
+    }
+
+    fn matched_newline(&self, start: usize, end: usize) -> Newline {
+        let Some(nl) = Newline::iter().find(|nl| nl.as_utf16() == &self[start..end]) else {
+            unreachable!("NewlinePattern should only match newline sequences");
+        };
+        nl
+    }
+
+    fn newline_window_end(&self) -> &[u16] {
+        // No newline sequence is more than two code units long.
+        &self[self.len().saturating_sub(2)..]
+    }
+
+    fn newline_window_at(&self, idx: usize) -> Option<&[u16]> {
+        let rest = self.get(idx..)?;
+        Some(&rest[..rest.len().min(2)])
+    }
+}
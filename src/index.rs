@@ -0,0 +1,396 @@
+use crate::nlset::NewlineSet;
+use crate::pattern::NewlinePattern;
+
+/// An index of the line breaks in a string for converting between byte
+/// offsets and (line, column) positions
+///
+/// A `LineIndex` is built from a string and a [`NewlineSet`] by locating
+/// every newline sequence in the set once, after which positions can be
+/// looked up by binary search.  As when splitting lines, a CR LF pair counts
+/// as a single line break (if [`Newline::CrLf`][crate::Newline::CrLf] is in
+/// the set), and U+2028 LINE SEPARATOR and U+2029 PARAGRAPH SEPARATOR only
+/// break lines if they are in the set.
+///
+/// Lines and columns are numbered starting from zero.  Columns are measured in
+/// bytes from the start of the line by [`line_col()`][LineIndex::line_col]
+/// and [`offset()`][LineIndex::offset]; the `*_with()` variants of these
+/// methods measure columns in a given [`ColumnEncoding`] instead, and
+/// [`position()`][LineIndex::position] and
+/// [`position_offset()`][LineIndex::position_offset] convert to & from
+/// Language Server Protocol [`Position`]s.  Unlike
+/// [`NewlineSet::lines()`], a newline at the end of the string is regarded as
+/// starting a final empty line, so that every offset from zero through the
+/// length of the string (inclusive) lies on some line.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{LineIndex, NewlineSet};
+///
+/// let index = LineIndex::new("foo\r\nbar\u{2028}baz\n", NewlineSet::UNICODE);
+/// assert_eq!(index.line_count(), 4);
+/// assert_eq!(index.line_col(6), Some((1, 1)));
+/// assert_eq!(index.line_col(11), Some((2, 0)));
+/// assert_eq!(index.offset(2, 2), Some(13));
+/// assert_eq!(index.line_span(0), Some((0, 5)));
+/// assert_eq!(index.line_span(3), Some((15, 15)));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LineIndex {
+    nlset: NewlineSet,
+
+    /// The start & end byte offsets of the content of each line
+    lines: Vec<(usize, usize)>,
+
+    /// The byte offsets of all non-ASCII characters in the string, along with
+    /// the characters themselves
+    wide_chars: Vec<(usize, char)>,
+
+    /// The length of the indexed string
+    len: usize,
+}
+
+impl LineIndex {
+    /// Create a new `LineIndex` for `s` that breaks lines on the newline
+    /// sequences in `nlset`
+    pub fn new(s: &str, nlset: NewlineSet) -> LineIndex {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while let Some((nl_start, nl_end)) = nlset.search(&s[start..]) {
+            lines.push((start, start + nl_start));
+            start += nl_end;
+        }
+        lines.push((start, s.len()));
+        let wide_chars = s.char_indices().filter(|(_, ch)| !ch.is_ascii()).collect();
+        LineIndex {
+            nlset,
+            lines,
+            wide_chars,
+            len: s.len(),
+        }
+    }
+
+    /// Returns the `NewlineSet` that the index breaks lines on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns the number of lines in the indexed string.  This is always at
+    /// least one, as even the empty string consists of a single empty line.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the zero-based line number and column of the byte at `offset`
+    /// in the indexed string, or `None` if `offset` is greater than the
+    /// length of the string.
+    ///
+    /// An offset within or at the start of a newline sequence is reported as
+    /// a column past the end of the content of the line that the newline
+    /// sequence terminates.
+    pub fn line_col(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.len {
+            return None;
+        }
+        let line = self
+            .lines
+            .partition_point(|&(start, _)| start <= offset)
+            .saturating_sub(1);
+        Some((line, offset - self.lines[line].0))
+    }
+
+    /// Returns the byte offset in the indexed string of the given zero-based
+    /// line number and column, or `None` if there is no such line or if
+    /// `col` is greater than the length of the content of the line.
+    pub fn offset(&self, line: usize, col: usize) -> Option<usize> {
+        let &(start, end) = self.lines.get(line)?;
+        let offset = start.checked_add(col)?;
+        (offset <= end).then_some(offset)
+    }
+
+    /// Returns the zero-based line number and column of the character at byte
+    /// offset `offset` in the indexed string, with the column measured in the
+    /// units of `encoding`.  Returns `None` if `offset` is greater than the
+    /// length of the string or, when `encoding` is not
+    /// [`ColumnEncoding::Utf8`], if `offset` is not on a character boundary.
+    pub fn line_col_with(&self, offset: usize, encoding: ColumnEncoding) -> Option<(usize, usize)> {
+        let (line, col) = self.line_col(offset)?;
+        if encoding == ColumnEncoding::Utf8 {
+            return Some((line, col));
+        }
+        let mut units = col;
+        for &(pos, ch) in self.wide_chars_between(offset - col, offset) {
+            if pos + ch.len_utf8() > offset {
+                return None;
+            }
+            units -= ch.len_utf8() - encoding.char_len(ch);
+        }
+        Some((line, units))
+    }
+
+    /// Returns the byte offset in the indexed string of the given zero-based
+    /// line number and column, with the column measured in the units of
+    /// `encoding`.  Returns `None` if there is no such line, if `col` is
+    /// greater than the length of the content of the line, or if `col` falls
+    /// in the middle of a character.
+    pub fn offset_with(&self, line: usize, col: usize, encoding: ColumnEncoding) -> Option<usize> {
+        if encoding == ColumnEncoding::Utf8 {
+            let offset = self.offset(line, col)?;
+            return match self.wide_chars_between(offset - col, offset).last() {
+                Some(&(pos, ch)) if pos + ch.len_utf8() > offset => None,
+                _ => Some(offset),
+            };
+        }
+        let &(start, end) = self.lines.get(line)?;
+        // Invariant: `units` is the length in `encoding` of
+        // `text[start..offset]`, and `units <= col`.
+        let mut offset = start;
+        let mut units = 0;
+        for &(pos, ch) in self.wide_chars_between(start, end) {
+            let ascii_len = pos - offset;
+            if col - units <= ascii_len {
+                break;
+            }
+            units += ascii_len;
+            let char_len = encoding.char_len(ch);
+            if col - units < char_len {
+                return None;
+            }
+            units += char_len;
+            offset = pos + ch.len_utf8();
+        }
+        let offset = offset.checked_add(col - units)?;
+        (offset <= end).then_some(offset)
+    }
+
+    /// Returns the Language Server Protocol [`Position`] of the character at
+    /// byte offset `offset` in the indexed string, with the `character` field
+    /// measured in the units of `encoding`.  Returns `None` under the same
+    /// circumstances as [`line_col_with()`][LineIndex::line_col_with] or if
+    /// either field of the position would not fit in a `u32`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{ColumnEncoding, LineIndex, NewlineSet, Position};
+    ///
+    /// let index = LineIndex::new("foo\r\n\u{1F600} = bar\n", NewlineSet::LSP);
+    /// assert_eq!(
+    ///     index.position(10, ColumnEncoding::Utf16),
+    ///     Some(Position { line: 1, character: 3 })
+    /// );
+    /// assert_eq!(
+    ///     index.position(10, ColumnEncoding::Utf32),
+    ///     Some(Position { line: 1, character: 2 })
+    /// );
+    /// assert_eq!(
+    ///     index.position_offset(Position { line: 1, character: 3 }, ColumnEncoding::Utf16),
+    ///     Some(10)
+    /// );
+    /// // The middle of a surrogate pair:
+    /// assert_eq!(
+    ///     index.position_offset(Position { line: 1, character: 1 }, ColumnEncoding::Utf16),
+    ///     None
+    /// );
+    /// ```
+    pub fn position(&self, offset: usize, encoding: ColumnEncoding) -> Option<Position> {
+        let (line, col) = self.line_col_with(offset, encoding)?;
+        Some(Position {
+            line: u32::try_from(line).ok()?,
+            character: u32::try_from(col).ok()?,
+        })
+    }
+
+    /// Returns the byte offset in the indexed string of the given Language
+    /// Server Protocol [`Position`], with the `character` field measured in
+    /// the units of `encoding`.  Returns `None` under the same circumstances
+    /// as [`offset_with()`][LineIndex::offset_with].
+    pub fn position_offset(&self, pos: Position, encoding: ColumnEncoding) -> Option<usize> {
+        let line = usize::try_from(pos.line).ok()?;
+        let col = usize::try_from(pos.character).ok()?;
+        self.offset_with(line, col, encoding)
+    }
+
+    /// Returns the start & end byte offsets of the given zero-based line,
+    /// including its terminating newline sequence (if any), or `None` if
+    /// there is no such line
+    pub fn line_span(&self, line: usize) -> Option<(usize, usize)> {
+        let &(start, _) = self.lines.get(line)?;
+        let end = self.lines.get(line + 1).map_or(self.len, |&(next, _)| next);
+        Some((start, end))
+    }
+
+    /// Returns the entries of `wide_chars` for the characters that start at
+    /// or after byte offset `start` and before byte offset `end`
+    fn wide_chars_between(&self, start: usize, end: usize) -> &[(usize, char)] {
+        let lo = self.wide_chars.partition_point(|&(pos, _)| pos < start);
+        let hi = self.wide_chars.partition_point(|&(pos, _)| pos < end);
+        &self.wide_chars[lo..hi]
+    }
+}
+
+/// The units in which the columns of a [`LineIndex`] are measured
+///
+/// These correspond to the position encodings of the Language Server
+/// Protocol, which measures columns in UTF-16 code units by default.
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ColumnEncoding {
+    /// Columns are measured in bytes of the UTF-8 encoding of the text
+    Utf8,
+
+    /// Columns are measured in UTF-16 code units
+    Utf16,
+
+    /// Columns are measured in Unicode code points (i.e., `char`s)
+    Utf32,
+}
+
+impl ColumnEncoding {
+    /// Returns the length of `ch` in the units of this encoding
+    fn char_len(self, ch: char) -> usize {
+        match self {
+            ColumnEncoding::Utf8 => ch.len_utf8(),
+            ColumnEncoding::Utf16 => ch.len_utf16(),
+            ColumnEncoding::Utf32 => 1,
+        }
+    }
+}
+
+/// A position in a text document as a zero-based line number and column, as
+/// used by the Language Server Protocol
+///
+/// The units in which `character` is measured are determined by the
+/// [`ColumnEncoding`] passed to [`LineIndex::position()`] and
+/// [`LineIndex::position_offset()`].
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    /// The zero-based line number
+    pub line: u32,
+
+    /// The zero-based column within the line
+    pub character: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nl::Newline;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", NewlineSet::UNICODE, 1)]
+    #[case("foo", NewlineSet::UNICODE, 1)]
+    #[case("foo\n", NewlineSet::UNICODE, 2)]
+    #[case("foo\r\nbar", NewlineSet::ASCII, 2)]
+    #[case("foo\r\nbar", Newline::LineFeed.into(), 2)]
+    #[case("foo\r\nbar", Newline::CarriageReturn.into(), 2)]
+    #[case("\r\r\n\n\r", NewlineSet::ASCII, 5)]
+    #[case("foo\u{2028}bar\u{2029}baz", NewlineSet::UNICODE, 3)]
+    #[case("foo\u{2028}bar\u{2029}baz", NewlineSet::ASCII, 1)]
+    #[case("foo\u{2028}bar\u{2029}baz", NewlineSet::NLF, 1)]
+    fn matches_lines(#[case] s: &str, #[case] nlset: NewlineSet, #[case] count: usize) {
+        let index = LineIndex::new(s, nlset);
+        assert_eq!(index.line_count(), count);
+        let lines = nlset.lines_with_newlines(s).collect::<Vec<_>>();
+        if lines.last().map_or(true, |ln| ln.newline().is_some()) {
+            assert_eq!(index.line_span(lines.len()), Some((s.len(), s.len())));
+        }
+        for (i, line) in lines.into_iter().enumerate() {
+            assert_eq!(index.line_span(i), Some(line.span()));
+            let (start, end) = line.span();
+            let (_, content_end) = line.content_span();
+            for offset in start..end {
+                assert_eq!(index.line_col(offset), Some((i, offset - start)));
+                let col = offset - start;
+                let expected = (offset <= content_end).then_some(offset);
+                assert_eq!(index.offset(i, col), expected);
+            }
+        }
+        assert_eq!(index.line_span(count), None);
+        assert_eq!(index.line_col(s.len() + 1), None);
+        assert_eq!(index.offset(count, 0), None);
+    }
+
+    #[rstest]
+    #[case("", NewlineSet::LSP)]
+    #[case("foo\r\nbar", NewlineSet::LSP)]
+    #[case("caf\u{e9}\r\n\u{1F600}\u{20ac}x\n", NewlineSet::LSP)]
+    #[case("a\u{2028}\u{1F600}\u{0085}b\u{2029}", NewlineSet::LSP)]
+    #[case("a\u{2028}\u{1F600}\u{0085}b\u{2029}", NewlineSet::UNICODE)]
+    fn column_encodings(#[case] s: &str, #[case] nlset: NewlineSet) {
+        let index = LineIndex::new(s, nlset);
+        for offset in 0..=s.len() {
+            let Some((line, col)) = index.line_col(offset) else {
+                unreachable!("Offset within string should have a position");
+            };
+            let line_start = offset - col;
+            for encoding in [
+                ColumnEncoding::Utf8,
+                ColumnEncoding::Utf16,
+                ColumnEncoding::Utf32,
+            ] {
+                let expected = s.get(line_start..offset).map(|prefix| match encoding {
+                    ColumnEncoding::Utf8 => prefix.len(),
+                    ColumnEncoding::Utf16 => prefix.encode_utf16().count(),
+                    ColumnEncoding::Utf32 => prefix.chars().count(),
+                });
+                if encoding == ColumnEncoding::Utf8 {
+                    assert_eq!(index.line_col_with(offset, encoding), Some((line, col)));
+                } else {
+                    assert_eq!(
+                        index.line_col_with(offset, encoding),
+                        expected.map(|c| (line, c)),
+                        "offset={offset}, encoding={encoding:?}"
+                    );
+                }
+                if let Some(c) = expected {
+                    let (_, content_end) = index.lines[line];
+                    assert_eq!(
+                        index.offset_with(line, c, encoding),
+                        (offset <= content_end).then_some(offset),
+                        "offset={offset}, encoding={encoding:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn offset_with_invalid() {
+        let index = LineIndex::new("\u{1F600}x\r\ny", NewlineSet::LSP);
+        assert_eq!(index.offset_with(0, 1, ColumnEncoding::Utf16), None);
+        assert_eq!(index.offset_with(0, 2, ColumnEncoding::Utf16), Some(4));
+        assert_eq!(index.offset_with(0, 3, ColumnEncoding::Utf16), Some(5));
+        assert_eq!(index.offset_with(0, 4, ColumnEncoding::Utf16), None);
+        assert_eq!(index.offset_with(0, 2, ColumnEncoding::Utf32), Some(5));
+        assert_eq!(index.offset_with(0, 3, ColumnEncoding::Utf32), None);
+        assert_eq!(index.offset_with(0, 1, ColumnEncoding::Utf8), None);
+        assert_eq!(index.offset_with(0, 3, ColumnEncoding::Utf8), None);
+        assert_eq!(index.offset_with(0, 4, ColumnEncoding::Utf8), Some(4));
+        assert_eq!(index.offset_with(0, 5, ColumnEncoding::Utf8), Some(5));
+        assert_eq!(index.offset_with(0, 6, ColumnEncoding::Utf8), None);
+        assert_eq!(index.offset_with(2, 0, ColumnEncoding::Utf16), None);
+        assert_eq!(
+            index.position(8, ColumnEncoding::Utf16),
+            Some(Position {
+                line: 1,
+                character: 1
+            })
+        );
+        assert_eq!(index.position(2, ColumnEncoding::Utf16), None);
+        assert_eq!(index.position(9, ColumnEncoding::Utf16), None);
+    }
+
+    #[test]
+    fn end_of_string() {
+        let index = LineIndex::new("foo\nbar", NewlineSet::ASCII);
+        assert_eq!(index.line_col(7), Some((1, 3)));
+        assert_eq!(index.offset(1, 3), Some(7));
+        assert_eq!(index.offset(1, 4), None);
+        assert_eq!(index.offset(0, 3), Some(3));
+        assert_eq!(index.offset(0, 4), None);
+    }
+}
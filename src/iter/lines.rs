@@ -0,0 +1,119 @@
+use super::split::SplitInternal;
+use crate::haystack::Haystack;
+use crate::pattern::NewlinePattern;
+use core::iter::FusedIterator;
+
+/// Iterator over the lines of a string, split on the newline sequences matched
+/// by a [`NewlinePattern`].  Lines are yielded as string slices without their
+/// terminating newline sequences.
+///
+/// As with [`str::lines()`], the final line may or may not end with a newline
+/// sequence, and a newline sequence at the end of the string does not produce
+/// a trailing empty line.  When the pattern is [`NewlineSet::RUST`], the lines
+/// yielded are exactly the same as those yielded by [`str::lines()`].
+///
+/// `Lines` is double-ended; lines taken from the back are found by searching
+/// backwards with [`NewlinePattern::rsearch()`], so the end of the string can
+/// be reached without scanning it from the start.
+///
+/// A `Lines` instance is acquired by calling [`Newline::lines()`] or
+/// [`NewlineSet::lines()`].
+///
+/// [`Newline::lines()`]: crate::Newline::lines
+/// [`NewlineSet::lines()`]: crate::NewlineSet::lines
+/// [`NewlineSet::RUST`]: crate::NewlineSet::RUST
+#[derive(Debug, Eq, PartialEq)]
+pub struct Lines<'a, P, H: ?Sized = str>(SplitInternal<'a, P, H>);
+
+impl<P: Clone, H: ?Sized> Clone for Lines<'_, P, H> {
+    fn clone(&self) -> Self {
+        Lines(self.0.clone())
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> Lines<'a, P, H> {
+    pub(crate) fn new(pattern: P, s: &'a H) -> Self {
+        Lines(SplitInternal::new(pattern, s, false))
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> Iterator for Lines<'a, P, H> {
+    type Item = &'a H;
+
+    fn next(&mut self) -> Option<&'a H> {
+        self.0.next().map(|line| line.content())
+    }
+
+    fn last(mut self) -> Option<&'a H> {
+        self.next_back()
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> DoubleEndedIterator for Lines<'a, P, H> {
+    fn next_back(&mut self) -> Option<&'a H> {
+        self.0.next_back().map(|line| line.content())
+    }
+}
+
+impl<P: NewlinePattern, H: Haystack + ?Sized> FusedIterator for Lines<'_, P, H> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Newline, NewlineSet};
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("")]
+    #[case("foo")]
+    #[case("foo\n")]
+    #[case("foo\r\n")]
+    #[case("foo\r")]
+    #[case("\n")]
+    #[case("\n\n")]
+    #[case("\r\n\r\n")]
+    #[case("foo\nbar")]
+    #[case("foo\r\nbar\n")]
+    #[case("foo\rbar\r\nbaz")]
+    #[case("foo\n\rbar")]
+    #[case("foo\r\r\nbar")]
+    #[case("foo\n\nbar\n\n")]
+    #[case("foo\u{2028}bar\u{0085}baz\x0Bquux\x0C")]
+    fn rust_matches_str_lines(#[case] s: &str) {
+        assert_eq!(
+            NewlineSet::RUST.lines(s).collect_vec(),
+            s.lines().collect_vec()
+        );
+        assert_eq!(
+            NewlineSet::RUST.lines(s).rev().collect_vec(),
+            s.lines().rev().collect_vec()
+        );
+    }
+
+    #[rstest]
+    #[case(NewlineSet::ASCII, "foo\rbar\r\nbaz\nquux", vec!["foo", "bar", "baz", "quux"])]
+    #[case(NewlineSet::ASCII, "foo\n\rbar\r", vec!["foo", "", "bar"])]
+    #[case(NewlineSet::ASCII, "foo\r\r\n", vec!["foo", ""])]
+    #[case(NewlineSet::EMPTY, "foo\nbar\r\n", vec!["foo\nbar\r\n"])]
+    #[case(NewlineSet::UNICODE, "foo\u{2028}bar\u{0085}baz\x0Bquux\x0C", vec!["foo", "bar", "baz", "quux"])]
+    #[case(NewlineSet::NLF, "foo\u{2028}bar\u{0085}baz", vec!["foo\u{2028}bar", "baz"])]
+    #[case(Newline::CrLf.into(), "foo\rbar\r\n\nbaz", vec!["foo\rbar", "\nbaz"])]
+    fn nlset_lines(#[case] nlset: NewlineSet, #[case] s: &str, #[case] mut lines: Vec<&str>) {
+        assert_eq!(nlset.lines(s).collect_vec(), lines);
+        assert_eq!(nlset.lines(s).last(), lines.last().copied());
+        lines.reverse();
+        assert_eq!(nlset.lines(s).rev().collect_vec(), lines);
+    }
+
+    #[rstest]
+    #[case(Newline::LineFeed, "foo\r\nbar\n", vec!["foo\r", "bar"])]
+    #[case(Newline::CarriageReturn, "foo\r\nbar\r", vec!["foo", "\nbar"])]
+    #[case(Newline::CrLf, "foo\r\nbar\rbaz\n", vec!["foo", "bar\rbaz\n"])]
+    #[case(Newline::ParagraphSeparator, "foo\u{2029}\u{2029}bar", vec!["foo", "", "bar"])]
+    #[case(Newline::LineFeed, "", vec![])]
+    fn newline_lines(#[case] nl: Newline, #[case] s: &str, #[case] mut lines: Vec<&str>) {
+        assert_eq!(nl.lines(s).collect_vec(), lines);
+        lines.reverse();
+        assert_eq!(nl.lines(s).rev().collect_vec(), lines);
+    }
+}
@@ -0,0 +1,51 @@
+use super::split::SplitInternal;
+use crate::haystack::Haystack;
+use crate::pattern::NewlinePattern;
+use core::iter::FusedIterator;
+
+/// Iterator over the lines of a string, split on the newline sequences matched
+/// by a [`NewlinePattern`].  Lines are yielded as string slices that include
+/// their terminating newline sequences.
+///
+/// As with [`str::split_inclusive()`], a newline sequence at the end of the
+/// string does not produce a trailing empty line.  Concatenating the yielded
+/// lines reproduces the original string.
+///
+/// A `LinesInclusive` instance is acquired by calling
+/// [`NewlineStrExt::split_lines_inclusive()`].
+///
+/// [`NewlineStrExt::split_lines_inclusive()`]: crate::NewlineStrExt::split_lines_inclusive
+#[derive(Debug, Eq, PartialEq)]
+pub struct LinesInclusive<'a, P, H: ?Sized = str>(SplitInternal<'a, P, H>);
+
+impl<P: Clone, H: ?Sized> Clone for LinesInclusive<'_, P, H> {
+    fn clone(&self) -> Self {
+        LinesInclusive(self.0.clone())
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> LinesInclusive<'a, P, H> {
+    pub(crate) fn new(pattern: P, s: &'a H) -> Self {
+        LinesInclusive(SplitInternal::new(pattern, s, false))
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> Iterator for LinesInclusive<'a, P, H> {
+    type Item = &'a H;
+
+    fn next(&mut self) -> Option<&'a H> {
+        self.0.next().map(|line| line.text())
+    }
+
+    fn last(mut self) -> Option<&'a H> {
+        self.next_back()
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> DoubleEndedIterator for LinesInclusive<'a, P, H> {
+    fn next_back(&mut self) -> Option<&'a H> {
+        self.0.next_back().map(|line| line.text())
+    }
+}
+
+impl<P: NewlinePattern, H: Haystack + ?Sized> FusedIterator for LinesInclusive<'_, P, H> {}
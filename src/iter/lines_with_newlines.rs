@@ -0,0 +1,139 @@
+use super::split::SplitInternal;
+use crate::haystack::Haystack;
+use crate::line::Line;
+use crate::pattern::NewlinePattern;
+use core::iter::FusedIterator;
+
+/// Iterator over the lines of a haystack, split on the newline sequences
+/// matched by a [`NewlinePattern`].  Each line is yielded as a [`Line`] that
+/// records the line's content, the [`Newline`][crate::Newline] that
+/// terminated it, and the offsets (in units of the haystack's elements) of
+/// both in the original haystack.
+///
+/// Lines are split the same way as by [`Lines`][super::Lines]: a newline
+/// sequence at the end of the haystack does not produce a trailing empty
+/// line, and the final line, if it does not end with a newline sequence, has a
+/// [`Line::newline()`] of `None`.
+///
+/// A `LinesWithNewlines` instance is acquired by calling
+/// [`Newline::lines_with_newlines()`] or
+/// [`NewlineSet::lines_with_newlines()`].
+///
+/// [`Newline::lines_with_newlines()`]: crate::Newline::lines_with_newlines
+/// [`NewlineSet::lines_with_newlines()`]: crate::NewlineSet::lines_with_newlines
+#[derive(Debug, Eq, PartialEq)]
+pub struct LinesWithNewlines<'a, P, H: ?Sized = str>(SplitInternal<'a, P, H>);
+
+impl<P: Clone, H: ?Sized> Clone for LinesWithNewlines<'_, P, H> {
+    fn clone(&self) -> Self {
+        LinesWithNewlines(self.0.clone())
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> LinesWithNewlines<'a, P, H> {
+    pub(crate) fn new(pattern: P, s: &'a H) -> Self {
+        LinesWithNewlines(SplitInternal::new(pattern, s, false))
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> Iterator for LinesWithNewlines<'a, P, H> {
+    type Item = Line<'a, H>;
+
+    fn next(&mut self) -> Option<Line<'a, H>> {
+        self.0.next()
+    }
+
+    fn last(mut self) -> Option<Line<'a, H>> {
+        self.next_back()
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> DoubleEndedIterator
+    for LinesWithNewlines<'a, P, H>
+{
+    fn next_back(&mut self) -> Option<Line<'a, H>> {
+        self.0.next_back()
+    }
+}
+
+impl<P: NewlinePattern, H: Haystack + ?Sized> FusedIterator for LinesWithNewlines<'_, P, H> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Newline, NewlineSet};
+    use itertools::Itertools;
+
+    #[test]
+    fn empty() {
+        assert_eq!(NewlineSet::ASCII.lines_with_newlines("").count(), 0);
+    }
+
+    #[test]
+    fn ascii() {
+        let s = "foo\rbar\r\n\nbaz";
+        let lines = NewlineSet::ASCII.lines_with_newlines(s).collect_vec();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].content(), "foo");
+        assert_eq!(lines[0].newline(), Some(Newline::CarriageReturn));
+        assert_eq!(lines[0].as_str(), "foo\r");
+        assert_eq!(lines[0].content_span(), (0, 3));
+        assert_eq!(lines[0].newline_span(), Some((3, 4)));
+        assert_eq!(lines[0].span(), (0, 4));
+        assert_eq!(lines[1].content(), "bar");
+        assert_eq!(lines[1].newline(), Some(Newline::CrLf));
+        assert_eq!(lines[1].as_str(), "bar\r\n");
+        assert_eq!(lines[1].content_span(), (4, 7));
+        assert_eq!(lines[1].newline_span(), Some((7, 9)));
+        assert_eq!(lines[1].span(), (4, 9));
+        assert_eq!(lines[2].content(), "");
+        assert_eq!(lines[2].newline(), Some(Newline::LineFeed));
+        assert_eq!(lines[2].content_span(), (9, 9));
+        assert_eq!(lines[2].newline_span(), Some((9, 10)));
+        assert_eq!(lines[3].content(), "baz");
+        assert_eq!(lines[3].newline(), None);
+        assert_eq!(lines[3].as_str(), "baz");
+        assert_eq!(lines[3].content_span(), (10, 13));
+        assert_eq!(lines[3].newline_span(), None);
+        assert_eq!(lines[3].span(), (10, 13));
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let s = "foo\u{2028}bar\u{2029}";
+        let lines = NewlineSet::UNICODE.lines_with_newlines(s).collect_vec();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].content(), "foo");
+        assert_eq!(lines[0].newline(), Some(Newline::LineSeparator));
+        assert_eq!(lines[0].newline_span(), Some((3, 6)));
+        assert_eq!(lines[1].content(), "bar");
+        assert_eq!(lines[1].newline(), Some(Newline::ParagraphSeparator));
+        assert_eq!(lines[1].content_span(), (6, 9));
+        assert_eq!(lines[1].newline_span(), Some((9, 12)));
+    }
+
+    #[test]
+    fn spans_reassemble_string() {
+        let s = "foo\r\n\u{0085}bar\x0Bbaz\x0C\r\rquux\n";
+        let mut rebuilt = String::new();
+        for line in NewlineSet::UNICODE.lines_with_newlines(s) {
+            let (start, end) = line.content_span();
+            assert_eq!(&s[start..end], line.content());
+            if let Some((nl_start, nl_end)) = line.newline_span() {
+                assert_eq!(nl_start, end);
+                assert_eq!(Newline::try_from(&s[nl_start..nl_end]).ok(), line.newline());
+            }
+            rebuilt.push_str(line.as_str());
+        }
+        assert_eq!(rebuilt, s);
+    }
+
+    #[test]
+    fn single_newline() {
+        let s = "foo\nbar\r\n";
+        let lines = Newline::LineFeed.lines_with_newlines(s).collect_vec();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].content(), "foo");
+        assert_eq!(lines[1].content(), "bar\r");
+        assert_eq!(lines[1].newline(), Some(Newline::LineFeed));
+    }
+}
@@ -4,12 +4,25 @@ mod diff;
 mod inner;
 mod intersection;
 mod into_iter;
+mod lines;
+mod lines_inclusive;
+mod lines_with_newlines;
+mod rsplitn;
+mod split;
+mod split_lines;
+mod splitn;
 mod symdiff;
 mod union;
 pub use self::complement::*;
 pub use self::diff::*;
 pub use self::intersection::*;
 pub use self::into_iter::*;
+pub use self::lines::*;
+pub use self::lines_inclusive::*;
+pub use self::lines_with_newlines::*;
+pub use self::rsplitn::*;
+pub use self::split_lines::*;
+pub use self::splitn::*;
 pub use self::symdiff::*;
 pub use self::union::*;
 use crate::nl::{CharType, Newline};
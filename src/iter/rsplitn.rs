@@ -0,0 +1,86 @@
+use super::split::SplitInternal;
+use crate::line::Line;
+use crate::pattern::NewlinePattern;
+use core::iter::FusedIterator;
+
+/// Iterator over at most `n` substrings of a string, split on the newline
+/// sequences matched by a [`NewlinePattern`], starting from the end of the
+/// string.  Each substring is yielded as a [`Line`] recording the
+/// [`Newline`][crate::Newline] that followed it.
+///
+/// As with [`str::rsplitn()`], the last substring yielded contains the
+/// remainder of the start of the string, including any newline sequences
+/// within it.  The first substring yielded is the portion of the string after
+/// the last newline sequence and has a [`Line::newline()`] of `None`.
+///
+/// A `RSplitN` instance is acquired by calling [`Newline::rsplitn()`] or
+/// [`NewlineSet::rsplitn()`].
+///
+/// [`Newline::rsplitn()`]: crate::Newline::rsplitn
+/// [`NewlineSet::rsplitn()`]: crate::NewlineSet::rsplitn
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RSplitN<'a, P> {
+    inner: SplitInternal<'a, P>,
+    count: usize,
+}
+
+impl<'a, P: NewlinePattern> RSplitN<'a, P> {
+    pub(crate) fn new(pattern: P, s: &'a str, n: usize) -> Self {
+        RSplitN {
+            inner: SplitInternal::new(pattern, s, true),
+            count: n,
+        }
+    }
+}
+
+impl<'a, P: NewlinePattern> Iterator for RSplitN<'a, P> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Line<'a>> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.get_end()
+            }
+            _ => {
+                self.count -= 1;
+                self.inner.next_back()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count))
+    }
+}
+
+impl<P: NewlinePattern> FusedIterator for RSplitN<'_, P> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Newline, NewlineSet};
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "foo\nbar", vec![])]
+    #[case(1, "foo\nbar\n", vec![("foo\nbar\n", None)])]
+    #[case(2, "foo\rbar\r\nbaz", vec![("baz", None), ("foo\rbar", Some(Newline::CrLf))])]
+    #[case(3, "foo\rbar\r\nbaz", vec![("baz", None), ("bar", Some(Newline::CrLf)), ("foo", Some(Newline::CarriageReturn))])]
+    #[case(5, "foo\nbar", vec![("bar", None), ("foo", Some(Newline::LineFeed))])]
+    #[case(5, "foo\n", vec![("", None), ("foo", Some(Newline::LineFeed))])]
+    #[case(2, "", vec![("", None)])]
+    fn rsplitn(#[case] n: usize, #[case] s: &str, #[case] expected: Vec<(&str, Option<Newline>)>) {
+        let parts = NewlineSet::ASCII
+            .rsplitn(n, s)
+            .map(|line| (line.content(), line.newline()))
+            .collect_vec();
+        assert_eq!(parts, expected);
+        let strparts = Newline::LineFeed
+            .rsplitn(n, s)
+            .map(|line| line.content())
+            .collect_vec();
+        assert_eq!(strparts, s.rsplitn(n, '\n').collect_vec());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::haystack::Haystack;
+use crate::line::Line;
+use crate::nl::Newline;
+use crate::pattern::NewlinePattern;
+
+/// The shared state machine behind the iterators that split a string on the
+/// newline sequences matched by a [`NewlinePattern`]
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct SplitInternal<'a, P, H: ?Sized = str> {
+    haystack: &'a H,
+    pattern: P,
+
+    /// The start of the portion of `haystack` that has not yet been yielded
+    start: usize,
+
+    /// The end of the portion of `haystack` that has not yet been yielded
+    end: usize,
+
+    /// The newline sequence that was matched immediately after `end` when
+    /// searching backwards, if any
+    end_newline: Option<Newline>,
+
+    /// The end of `end_newline`, or `end` if there is no such newline
+    nl_end: usize,
+
+    /// Whether to yield an empty segment after a newline sequence at the end
+    /// of `haystack`
+    allow_trailing_empty: bool,
+
+    finished: bool,
+}
+
+// Clone is implemented manually, as deriving it would require `H: Clone`
+impl<P: Clone, H: ?Sized> Clone for SplitInternal<'_, P, H> {
+    fn clone(&self) -> Self {
+        SplitInternal {
+            haystack: self.haystack,
+            pattern: self.pattern.clone(),
+            start: self.start,
+            end: self.end,
+            end_newline: self.end_newline,
+            nl_end: self.nl_end,
+            allow_trailing_empty: self.allow_trailing_empty,
+            finished: self.finished,
+        }
+    }
+}
+
+impl<'a, P: NewlinePattern, H: Haystack + ?Sized> SplitInternal<'a, P, H> {
+    pub(crate) fn new(pattern: P, haystack: &'a H, allow_trailing_empty: bool) -> Self {
+        SplitInternal {
+            haystack,
+            pattern,
+            start: 0,
+            end: haystack.haystack_len(),
+            end_newline: None,
+            nl_end: haystack.haystack_len(),
+            allow_trailing_empty,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn get_end(&mut self) -> Option<Line<'a, H>> {
+        if !self.finished {
+            self.finished = true;
+            if self.allow_trailing_empty || self.end > self.start {
+                return Some(self.end_line(self.start));
+            }
+        }
+        None
+    }
+
+    pub(crate) fn next(&mut self) -> Option<Line<'a, H>> {
+        if self.finished {
+            return None;
+        }
+        match self
+            .haystack
+            .slice(self.start, self.end)
+            .search_pattern(&self.pattern)
+        {
+            Some((a, b)) => {
+                let line = self.make_line(self.start, self.start + a, self.start + b);
+                self.start += b;
+                Some(line)
+            }
+            None => self.get_end(),
+        }
+    }
+
+    pub(crate) fn next_back(&mut self) -> Option<Line<'a, H>> {
+        if self.finished {
+            return None;
+        }
+        if !self.allow_trailing_empty {
+            self.allow_trailing_empty = true;
+            match self.next_back() {
+                Some(line) if line.content().haystack_len() > 0 => return Some(line),
+                _ => {
+                    if self.finished {
+                        return None;
+                    }
+                }
+            }
+        }
+        let Some((a, b)) = self
+            .haystack
+            .slice(self.start, self.end)
+            .rsearch_pattern(&self.pattern)
+        else {
+            self.finished = true;
+            return Some(self.end_line(self.start));
+        };
+        let line = self.end_line(self.start + b);
+        self.end_newline = Some(
+            self.haystack
+                .matched_newline(self.start + a, self.start + b),
+        );
+        self.end = self.start + a;
+        self.nl_end = self.start + b;
+        Some(line)
+    }
+
+    /// Construct a `Line` whose content is `haystack[start..nl_start]` and
+    /// whose newline sequence is `haystack[nl_start..nl_end]`
+    fn make_line(&self, start: usize, nl_start: usize, nl_end: usize) -> Line<'a, H> {
+        let nl = self.haystack.matched_newline(nl_start, nl_end);
+        Line::new(
+            self.haystack.slice(start, nl_end),
+            start,
+            nl_start - start,
+            Some(nl),
+        )
+    }
+
+    /// Construct a `Line` whose content is `haystack[start..end]` and whose
+    /// newline sequence is `end_newline`
+    fn end_line(&self, start: usize) -> Line<'a, H> {
+        Line::new(
+            self.haystack.slice(start, self.nl_end),
+            start,
+            self.end - start,
+            self.end_newline,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlset::NewlineSet;
+    use itertools::Itertools;
+
+    /// Check that every interleaving of forwards & backwards iteration over
+    /// `s` produces the same lines as iterating forwards
+    fn check_interleavings<P: NewlinePattern + Copy + core::fmt::Debug>(pattern: P, s: &str) {
+        for allow_trailing_empty in [false, true] {
+            let expected = {
+                let mut split = SplitInternal::new(pattern, s, allow_trailing_empty);
+                core::iter::from_fn(|| split.next()).collect_vec()
+            };
+            for mask in 0u32..(1 << (expected.len() + 1)) {
+                let mut split = SplitInternal::new(pattern, s, allow_trailing_empty);
+                let mut front = Vec::new();
+                let mut back = Vec::new();
+                for i in 0..=expected.len() {
+                    if mask & (1 << i) == 0 {
+                        let Some(line) = split.next() else { break };
+                        front.push(line);
+                    } else {
+                        let Some(line) = split.next_back() else { break };
+                        back.push(line);
+                    }
+                }
+                assert_eq!(split.next(), None);
+                assert_eq!(split.next_back(), None);
+                back.reverse();
+                front.extend(back);
+                assert_eq!(
+                    front, expected,
+                    "s={s:?}, pattern={pattern:?}, mask={mask:#b}, trailing={allow_trailing_empty}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn interleaved_iteration() {
+        let patterns = [
+            NewlineSet::RUST,
+            NewlineSet::ASCII,
+            NewlineSet::UNICODE,
+            Newline::CarriageReturn.into(),
+            Newline::CrLf.into(),
+            Newline::LineFeed | Newline::CarriageReturn,
+            Newline::CarriageReturn | Newline::CrLf,
+        ];
+        for len in 0..=5 {
+            for chars in core::iter::repeat(['a', '\r', '\n', '\u{2028}'])
+                .take(len)
+                .multi_cartesian_product()
+            {
+                let s = chars.into_iter().collect::<String>();
+                for nlset in patterns {
+                    check_interleavings(nlset, &s);
+                }
+                for nl in [Newline::LineFeed, Newline::CarriageReturn, Newline::CrLf] {
+                    check_interleavings(nl, &s);
+                }
+            }
+        }
+    }
+}
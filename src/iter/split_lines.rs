@@ -0,0 +1,71 @@
+use super::split::SplitInternal;
+use crate::nlset::NewlineSet;
+use core::iter::FusedIterator;
+
+/// Iterator over the lines of a string as split by a
+/// [`LineSplitter`][crate::LineSplitter].  Lines are yielded as string
+/// slices, with or without their terminating newline sequences depending on
+/// the splitter's configuration.
+///
+/// A `SplitLines` instance is acquired by calling
+/// [`LineSplitter::split()`][crate::LineSplitter::split].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitLines<'a> {
+    inner: SplitInternal<'a, NewlineSet>,
+    keep_newlines: bool,
+    skip_empty: bool,
+}
+
+impl<'a> SplitLines<'a> {
+    pub(crate) fn new(
+        nlset: NewlineSet,
+        s: &'a str,
+        keep_newlines: bool,
+        skip_empty: bool,
+        trailing_empty: bool,
+    ) -> Self {
+        SplitLines {
+            inner: SplitInternal::new(nlset, s, trailing_empty),
+            keep_newlines,
+            skip_empty,
+        }
+    }
+}
+
+impl<'a> Iterator for SplitLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            let line = self.inner.next()?;
+            if !(self.skip_empty && line.content().is_empty()) {
+                return Some(if self.keep_newlines {
+                    line.as_str()
+                } else {
+                    line.content()
+                });
+            }
+        }
+    }
+
+    fn last(mut self) -> Option<&'a str> {
+        self.next_back()
+    }
+}
+
+impl<'a> DoubleEndedIterator for SplitLines<'a> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        loop {
+            let line = self.inner.next_back()?;
+            if !(self.skip_empty && line.content().is_empty()) {
+                return Some(if self.keep_newlines {
+                    line.as_str()
+                } else {
+                    line.content()
+                });
+            }
+        }
+    }
+}
+
+impl FusedIterator for SplitLines<'_> {}
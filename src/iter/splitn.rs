@@ -0,0 +1,87 @@
+use super::split::SplitInternal;
+use crate::line::Line;
+use crate::pattern::NewlinePattern;
+use core::iter::FusedIterator;
+
+/// Iterator over at most `n` substrings of a string, split on the newline
+/// sequences matched by a [`NewlinePattern`], starting from the beginning of
+/// the string.  Each substring is yielded as a [`Line`] recording the
+/// [`Newline`][crate::Newline] that ended it.
+///
+/// As with [`str::splitn()`], the last substring yielded contains the
+/// remainder of the string, including any newline sequences within it, and
+/// has a [`Line::newline()`] of `None`.  Unlike [`Lines`][super::Lines], a
+/// newline sequence at the end of the string produces a trailing empty
+/// substring.
+///
+/// A `SplitN` instance is acquired by calling [`Newline::splitn()`] or
+/// [`NewlineSet::splitn()`].
+///
+/// [`Newline::splitn()`]: crate::Newline::splitn
+/// [`NewlineSet::splitn()`]: crate::NewlineSet::splitn
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitN<'a, P> {
+    inner: SplitInternal<'a, P>,
+    count: usize,
+}
+
+impl<'a, P: NewlinePattern> SplitN<'a, P> {
+    pub(crate) fn new(pattern: P, s: &'a str, n: usize) -> Self {
+        SplitN {
+            inner: SplitInternal::new(pattern, s, true),
+            count: n,
+        }
+    }
+}
+
+impl<'a, P: NewlinePattern> Iterator for SplitN<'a, P> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Line<'a>> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.get_end()
+            }
+            _ => {
+                self.count -= 1;
+                self.inner.next()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count))
+    }
+}
+
+impl<P: NewlinePattern> FusedIterator for SplitN<'_, P> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Newline, NewlineSet};
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "foo\nbar", vec![])]
+    #[case(1, "foo\nbar\n", vec![("foo\nbar\n", None)])]
+    #[case(2, "foo\r\nbar\rbaz", vec![("foo", Some(Newline::CrLf)), ("bar\rbaz", None)])]
+    #[case(3, "foo\r\nbar\rbaz", vec![("foo", Some(Newline::CrLf)), ("bar", Some(Newline::CarriageReturn)), ("baz", None)])]
+    #[case(5, "foo\nbar", vec![("foo", Some(Newline::LineFeed)), ("bar", None)])]
+    #[case(5, "foo\n", vec![("foo", Some(Newline::LineFeed)), ("", None)])]
+    #[case(2, "", vec![("", None)])]
+    fn splitn(#[case] n: usize, #[case] s: &str, #[case] expected: Vec<(&str, Option<Newline>)>) {
+        let parts = NewlineSet::ASCII
+            .splitn(n, s)
+            .map(|line| (line.content(), line.newline()))
+            .collect_vec();
+        assert_eq!(parts, expected);
+        let strparts = Newline::LineFeed
+            .splitn(n, s)
+            .map(|line| line.content())
+            .collect_vec();
+        assert_eq!(strparts, s.splitn(n, '\n').collect_vec());
+    }
+}
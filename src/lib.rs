@@ -1,11 +1,79 @@
 #![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#[cfg(feature = "std")]
+mod accumulator;
+mod assembler;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod async_bufread;
+#[cfg(feature = "bstr")]
+mod bstr_ext;
+#[cfg(feature = "std")]
+mod bufread;
+mod bytes;
 mod charset;
+#[cfg(feature = "tokio-util")]
+mod codec;
+mod detect;
+#[cfg(feature = "embedded-io-async")]
+mod embedded_io_async_ext;
+#[cfg(feature = "embedded-io")]
+mod embedded_io_ext;
+mod encoding;
 pub mod errors;
+mod ext;
+#[cfg(feature = "futures-io")]
+mod futures_io_ext;
+mod haystack;
+#[cfg(feature = "std")]
+mod index;
 pub mod iter;
+mod line;
 mod nl;
 mod nlset;
+#[cfg(feature = "std")]
+mod normalize;
 mod pattern;
+#[cfg(feature = "std")]
+mod reader;
+mod splitter;
+mod stats;
+#[cfg(feature = "tokio")]
+mod tokio_ext;
+mod utf16;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "std")]
+pub use self::accumulator::*;
+pub use self::assembler::*;
+#[cfg(feature = "bstr")]
+pub use self::bstr_ext::*;
+#[cfg(feature = "std")]
+pub use self::bufread::*;
+#[cfg(feature = "tokio-util")]
+pub use self::codec::*;
+pub use self::detect::*;
+#[cfg(feature = "embedded-io-async")]
+pub use self::embedded_io_async_ext::*;
+#[cfg(feature = "embedded-io")]
+pub use self::embedded_io_ext::*;
+pub use self::encoding::*;
+pub use self::ext::*;
+#[cfg(feature = "futures-io")]
+pub use self::futures_io_ext::*;
+pub use self::haystack::*;
+#[cfg(feature = "std")]
+pub use self::index::*;
+pub use self::line::*;
 pub use self::nl::*;
 pub use self::nlset::*;
+#[cfg(feature = "std")]
+pub use self::normalize::*;
 pub use self::pattern::*;
+#[cfg(feature = "std")]
+pub use self::reader::*;
+pub use self::splitter::*;
+pub use self::stats::*;
+#[cfg(feature = "tokio")]
+pub use self::tokio_ext::*;
+#[cfg(feature = "std")]
+pub use self::writer::*;
@@ -0,0 +1,153 @@
+use crate::haystack::Haystack;
+use crate::nl::Newline;
+use core::str::Utf8Error;
+
+/// A line of a string, along with the newline sequence (if any) that
+/// terminated it and the offsets at which the line's parts occur in the
+/// original string
+///
+/// `Line` values are yielded by
+/// [`LinesWithNewlines`][crate::iter::LinesWithNewlines].  The type parameter
+/// `T` is the type of text that was split into lines; it is `str` by default,
+/// `[u8]` for lines of byte slices, and `[u16]` for lines of UTF-16 code
+/// units.  All offsets are measured in units of `T`'s elements (i.e., bytes
+/// for `str` and `[u8]`, code units for `[u16]`).
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Line<'a, T: ?Sized = str> {
+    /// The line's content followed by its newline sequence (if any)
+    text: &'a T,
+
+    /// The offset in the original string at which the line starts, in units
+    /// of the haystack's elements (bytes for `str`/`[u8]`, code units for
+    /// `[u16]`)
+    start: usize,
+
+    /// The length of the line's content, i.e., the offset within `text` at
+    /// which the newline sequence starts
+    content_len: usize,
+
+    /// The newline sequence that terminated the line
+    newline: Option<Newline>,
+}
+
+// Clone and Copy are implemented manually, as deriving them would require
+// `T: Clone`, which `str` and `[u8]` do not satisfy.
+impl<T: ?Sized> Clone for Line<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Line<'_, T> {}
+
+impl<'a, T: Haystack + ?Sized> Line<'a, T> {
+    pub(crate) fn new(
+        text: &'a T,
+        start: usize,
+        content_len: usize,
+        newline: Option<Newline>,
+    ) -> Line<'a, T> {
+        Line {
+            text,
+            start,
+            content_len,
+            newline,
+        }
+    }
+
+    /// Returns the content of the line, without its terminating newline
+    /// sequence
+    pub fn content(&self) -> &'a T {
+        self.text.slice(0, self.content_len)
+    }
+
+    /// Returns the newline sequence that terminated the line, or `None` if
+    /// the line was the final line of the string and did not end with a
+    /// newline
+    pub fn newline(&self) -> Option<Newline> {
+        self.newline
+    }
+
+    /// Returns the content of the line followed by its terminating newline
+    /// sequence (if any)
+    pub(crate) fn text(&self) -> &'a T {
+        self.text
+    }
+
+    /// Returns the start & end offsets of the line's content in the original
+    /// string, in units of the haystack's elements (bytes for `str`/`[u8]`,
+    /// code units for `[u16]`)
+    pub fn content_span(&self) -> (usize, usize) {
+        (self.start, self.start + self.content_len)
+    }
+
+    /// Returns the start & end offsets of the line's terminating newline
+    /// sequence in the original string, in units of the haystack's elements
+    /// (bytes for `str`/`[u8]`, code units for `[u16]`), or `None` if the line
+    /// was not terminated by a newline
+    pub fn newline_span(&self) -> Option<(usize, usize)> {
+        self.newline?;
+        Some((
+            self.start + self.content_len,
+            self.start + self.text.haystack_len(),
+        ))
+    }
+
+    /// Returns the start & end offsets of the complete line, including its
+    /// terminating newline sequence, in the original string, in units of the
+    /// haystack's elements (bytes for `str`/`[u8]`, code units for `[u16]`)
+    pub fn span(&self) -> (usize, usize) {
+        (self.start, self.start + self.text.haystack_len())
+    }
+}
+
+impl<'a> Line<'a, str> {
+    /// Returns the content of the line followed by its terminating newline
+    /// sequence (if any)
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+}
+
+impl<'a> Line<'a, [u8]> {
+    /// Returns the content of the line followed by its terminating newline
+    /// sequence (if any)
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.text
+    }
+
+    /// Convert the line to a `Line<'a, str>` with the same newline and
+    /// offsets
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineBytesExt, NewlineSet};
+    ///
+    /// let mut lines = b"foo\r\n\xFFbar".lines_with_newlines(NewlineSet::ASCII);
+    /// let line = lines.next().unwrap().to_str().unwrap();
+    /// assert_eq!(line.as_str(), "foo\r\n");
+    /// assert_eq!(line.newline(), Some(Newline::CrLf));
+    /// assert!(lines.next().unwrap().to_str().is_err());
+    /// ```
+    pub fn to_str(&self) -> Result<Line<'a, str>, Utf8Error> {
+        Ok(Line {
+            text: core::str::from_utf8(self.text)?,
+            start: self.start,
+            content_len: self.content_len,
+            newline: self.newline,
+        })
+    }
+}
+
+impl<'a> Line<'a, [u16]> {
+    /// Returns the content of the line followed by its terminating newline
+    /// sequence (if any)
+    pub fn as_utf16(&self) -> &'a [u16] {
+        self.text
+    }
+}
@@ -1,4 +1,6 @@
 use super::errors::{TryFromCharError, TryFromStrError};
+use super::iter::{Lines, LinesWithNewlines, RSplitN, SplitN};
+use super::pattern::{rsplit_once, split_once};
 use core::fmt;
 use strum::{EnumCount, EnumIter};
 
@@ -53,7 +55,8 @@ pub enum Newline {
     CrLf,
 
     /// U+0085 NEXT LINE (NEL), the Unicode equivalent of the newline sequence
-    /// used on EBCDIC-based systems
+    /// used on EBCDIC-based systems (see
+    /// [`ByteEncoding::Ebcdic`][crate::ByteEncoding::Ebcdic])
     NextLine,
 
     /// U+2028 LINE SEPARATOR
@@ -168,6 +171,179 @@ impl Newline {
     pub fn len_utf8(&self) -> usize {
         self.as_str().len()
     }
+
+    /// Returns the UTF-16 encoding of the newline sequence
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// assert_eq!(Newline::LineFeed.as_utf16(), [0x000A]);
+    /// assert_eq!(Newline::CrLf.as_utf16(), [0x000D, 0x000A]);
+    /// assert_eq!(Newline::LineSeparator.as_utf16(), [0x2028]);
+    /// ```
+    pub fn as_utf16(&self) -> &'static [u16] {
+        match self {
+            Newline::LineFeed => &[0x000A],
+            Newline::VerticalTab => &[0x000B],
+            Newline::FormFeed => &[0x000C],
+            Newline::CarriageReturn => &[0x000D],
+            Newline::CrLf => &[0x000D, 0x000A],
+            Newline::NextLine => &[0x0085],
+            Newline::LineSeparator => &[0x2028],
+            Newline::ParagraphSeparator => &[0x2029],
+        }
+    }
+
+    /// Returns the number of 16-bit code units in the UTF-16 encoding of the
+    /// newline sequence.
+    ///
+    /// As all newline sequences consist of characters in the Basic
+    /// Multilingual Plane, this is always equal to
+    /// [`len_char()`][Newline::len_char].
+    pub fn len_utf16(&self) -> usize {
+        self.as_utf16().len()
+    }
+
+    /// Returns an iterator over the lines of `s`, split on occurrences of this
+    /// newline sequence.  The lines do not include their terminating newline
+    /// sequences, and a newline at the end of `s` does not produce a trailing
+    /// empty line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// let mut iter = Newline::CrLf.lines("foo\r\nbar\rbaz\r\n");
+    /// assert_eq!(iter.next(), Some("foo"));
+    /// assert_eq!(iter.next(), Some("bar\rbaz"));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn lines<'a>(&self, s: &'a str) -> Lines<'a, Newline> {
+        Lines::new(*self, s)
+    }
+
+    /// Returns an iterator over the lines of `s`, split on occurrences of this
+    /// newline sequence, as [`Line`][crate::Line] values that record the
+    /// newline sequence terminating each line along with the byte offsets of
+    /// the line's content and terminator.
+    ///
+    /// Lines are split the same way as by [`Newline::lines()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// let mut iter = Newline::LineFeed.lines_with_newlines("foo\nbar");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo");
+    /// assert_eq!(line.newline(), Some(Newline::LineFeed));
+    /// assert_eq!(line.newline_span(), Some((3, 4)));
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "bar");
+    /// assert_eq!(line.newline(), None);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn lines_with_newlines<'a>(&self, s: &'a str) -> LinesWithNewlines<'a, Newline> {
+        LinesWithNewlines::new(*self, s)
+    }
+
+    /// Returns an iterator over at most `n` substrings of `s`, split on
+    /// occurrences of this newline sequence, starting from the beginning of
+    /// `s`.  The substrings are yielded as [`Line`][crate::Line] values that
+    /// record the newline sequence following each substring.
+    ///
+    /// The last substring yielded contains the rest of `s`, including any
+    /// newline sequences, and its [`newline()`][crate::Line::newline] is
+    /// `None`.  A newline sequence at the end of `s` produces a trailing empty
+    /// substring, as with [`str::splitn()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// let mut iter = Newline::LineFeed.splitn(2, "foo\nbar\nbaz");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo");
+    /// assert_eq!(line.newline(), Some(Newline::LineFeed));
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "bar\nbaz");
+    /// assert_eq!(line.newline(), None);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn splitn<'a>(&self, n: usize, s: &'a str) -> SplitN<'a, Newline> {
+        SplitN::new(*self, s, n)
+    }
+
+    /// Returns an iterator over at most `n` substrings of `s`, split on
+    /// occurrences of this newline sequence, starting from the end of `s`.
+    /// The substrings are yielded as [`Line`][crate::Line] values that record
+    /// the newline sequence following each substring.
+    ///
+    /// The last substring yielded contains the rest of the start of `s`,
+    /// including any newline sequences, as with [`str::rsplitn()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// let mut iter = Newline::LineFeed.rsplitn(2, "foo\nbar\nbaz");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "baz");
+    /// assert_eq!(line.newline(), None);
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo\nbar");
+    /// assert_eq!(line.newline(), Some(Newline::LineFeed));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn rsplitn<'a>(&self, n: usize, s: &'a str) -> RSplitN<'a, Newline> {
+        RSplitN::new(*self, s, n)
+    }
+
+    /// Splits `s` on the first occurrence of this newline sequence, returning
+    /// the portion of `s` before the newline, the newline itself, and the
+    /// portion after the newline.  Returns `None` if there is no such newline
+    /// in `s`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// assert_eq!(
+    ///     Newline::LineFeed.split_once("foo\nbar\nbaz"),
+    ///     Some(("foo", Newline::LineFeed, "bar\nbaz")),
+    /// );
+    /// assert_eq!(Newline::LineFeed.split_once("foo\rbar"), None);
+    /// ```
+    pub fn split_once<'a>(&self, s: &'a str) -> Option<(&'a str, Newline, &'a str)> {
+        split_once(self, s)
+    }
+
+    /// Splits `s` on the last occurrence of this newline sequence, returning
+    /// the portion of `s` before the newline, the newline itself, and the
+    /// portion after the newline.  Returns `None` if there is no such newline
+    /// in `s`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::Newline;
+    ///
+    /// assert_eq!(
+    ///     Newline::LineFeed.rsplit_once("foo\nbar\nbaz"),
+    ///     Some(("foo\nbar", Newline::LineFeed, "baz")),
+    /// );
+    /// assert_eq!(Newline::LineFeed.rsplit_once("foo\rbar"), None);
+    /// ```
+    pub fn rsplit_once<'a>(&self, s: &'a str) -> Option<(&'a str, Newline, &'a str)> {
+        rsplit_once(self, s)
+    }
 }
 
 impl fmt::Display for Newline {
@@ -302,6 +478,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_as_utf16() {
+        for nl in Newline::iter() {
+            assert_eq!(
+                nl.as_utf16(),
+                nl.as_str().encode_utf16().collect::<Vec<_>>()
+            );
+            assert_eq!(nl.len_utf16(), nl.len_char());
+        }
+    }
+
     #[test]
     fn test_try_from_char() {
         for nl in Newline::iter() {
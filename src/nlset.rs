@@ -1,8 +1,10 @@
 use super::charset::{CharSet, Diff};
 use super::iter::{
-    AscendingNewlines, Complement, Difference, Intersection, IntoIter, SymmetricDifference, Union,
+    AscendingNewlines, Complement, Difference, Intersection, IntoIter, Lines, LinesWithNewlines,
+    RSplitN, SplitN, SymmetricDifference, Union,
 };
 use super::nl::{CharType, Newline};
+use super::pattern::{matched_newline, rsplit_once, split_once, NewlinePattern};
 use core::fmt;
 use core::ops;
 
@@ -92,6 +94,18 @@ impl NewlineSet {
         crlf: true,
     };
 
+    /// The newline sequences that the [Language Server Protocol][lsp]
+    /// recognizes as line endings when converting positions to offsets:
+    ///
+    /// - [`Newline::LineFeed`]
+    /// - [`Newline::CarriageReturn`]
+    /// - [`Newline::CrLf`]
+    ///
+    /// This is the same set as [`NewlineSet::ASCII`].
+    ///
+    /// [lsp]: https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocuments
+    pub const LSP: NewlineSet = NewlineSet::ASCII;
+
     /// Newline sequences classified as "newline functions" by Unicode §5.8,
     /// "Newline Guidelines":
     ///
@@ -387,6 +401,188 @@ impl NewlineSet {
     pub fn iter(&self) -> IntoIter {
         self.into_iter()
     }
+
+    /// Returns an iterator over the lines of `s`, split on occurrences of any
+    /// newline sequence in the set.  The lines do not include their
+    /// terminating newline sequences, and a newline at the end of `s` does not
+    /// produce a trailing empty line.
+    ///
+    /// When the set contains [`Newline::CrLf`], a CR LF pair is always treated
+    /// as a single newline sequence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::NewlineSet;
+    ///
+    /// let mut iter = NewlineSet::ASCII.lines("foo\rbar\r\nbaz\n");
+    /// assert_eq!(iter.next(), Some("foo"));
+    /// assert_eq!(iter.next(), Some("bar"));
+    /// assert_eq!(iter.next(), Some("baz"));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn lines<'a>(&self, s: &'a str) -> Lines<'a, NewlineSet> {
+        Lines::new(*self, s)
+    }
+
+    /// Returns an iterator over the lines of `s`, split on occurrences of any
+    /// newline sequence in the set, as [`Line`][crate::Line] values that
+    /// record the newline sequence terminating each line along with the byte
+    /// offsets of the line's content and terminator.
+    ///
+    /// Lines are split the same way as by [`NewlineSet::lines()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// let mut iter = NewlineSet::ASCII.lines_with_newlines("foo\r\nbar\r");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo");
+    /// assert_eq!(line.newline(), Some(Newline::CrLf));
+    /// assert_eq!(line.newline_span(), Some((3, 5)));
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "bar");
+    /// assert_eq!(line.newline(), Some(Newline::CarriageReturn));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn lines_with_newlines<'a>(&self, s: &'a str) -> LinesWithNewlines<'a, NewlineSet> {
+        LinesWithNewlines::new(*self, s)
+    }
+
+    /// Returns an iterator over at most `n` substrings of `s`, split on
+    /// occurrences of any newline sequence in the set, starting from the
+    /// beginning of `s`.  The substrings are yielded as [`Line`][crate::Line]
+    /// values that record the newline sequence following each substring.
+    ///
+    /// The last substring yielded contains the rest of `s`, including any
+    /// newline sequences, and its [`newline()`][crate::Line::newline] is
+    /// `None`.  A newline sequence at the end of `s` produces a trailing empty
+    /// substring, as with [`str::splitn()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// let mut iter = NewlineSet::ASCII.splitn(2, "foo\r\nbar\rbaz");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo");
+    /// assert_eq!(line.newline(), Some(Newline::CrLf));
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "bar\rbaz");
+    /// assert_eq!(line.newline(), None);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn splitn<'a>(&self, n: usize, s: &'a str) -> SplitN<'a, NewlineSet> {
+        SplitN::new(*self, s, n)
+    }
+
+    /// Returns an iterator over at most `n` substrings of `s`, split on
+    /// occurrences of any newline sequence in the set, starting from the end
+    /// of `s`.  The substrings are yielded as [`Line`][crate::Line] values
+    /// that record the newline sequence following each substring.
+    ///
+    /// The last substring yielded contains the rest of the start of `s`,
+    /// including any newline sequences, as with [`str::rsplitn()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// let mut iter = NewlineSet::ASCII.rsplitn(2, "foo\r\nbar\rbaz");
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "baz");
+    /// assert_eq!(line.newline(), None);
+    /// let line = iter.next().unwrap();
+    /// assert_eq!(line.content(), "foo\r\nbar");
+    /// assert_eq!(line.newline(), Some(Newline::CarriageReturn));
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn rsplitn<'a>(&self, n: usize, s: &'a str) -> RSplitN<'a, NewlineSet> {
+        RSplitN::new(*self, s, n)
+    }
+
+    /// Splits `s` on the first occurrence of any newline sequence in the set,
+    /// returning the portion of `s` before the newline, the newline itself,
+    /// and the portion after the newline.  Returns `None` if there is no such
+    /// newline in `s`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// assert_eq!(
+    ///     NewlineSet::ASCII.split_once("foo\r\nbar\rbaz"),
+    ///     Some(("foo", Newline::CrLf, "bar\rbaz")),
+    /// );
+    /// assert_eq!(NewlineSet::ASCII.split_once("foobar"), None);
+    /// ```
+    pub fn split_once<'a>(&self, s: &'a str) -> Option<(&'a str, Newline, &'a str)> {
+        split_once(self, s)
+    }
+
+    /// Splits `s` on the last occurrence of any newline sequence in the set,
+    /// returning the portion of `s` before the newline, the newline itself,
+    /// and the portion after the newline.  Returns `None` if there is no such
+    /// newline in `s`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// assert_eq!(
+    ///     NewlineSet::ASCII.rsplit_once("foo\rbar\r\nbaz"),
+    ///     Some(("foo\rbar", Newline::CrLf, "baz")),
+    /// );
+    /// assert_eq!(NewlineSet::ASCII.rsplit_once("foobar"), None);
+    /// ```
+    pub fn rsplit_once<'a>(&self, s: &'a str) -> Option<(&'a str, Newline, &'a str)> {
+        rsplit_once(self, s)
+    }
+
+    /// Returns the set of newline sequences in `self` that occur in `s`.
+    ///
+    /// Newline sequences are located the same way as when splitting `s` into
+    /// lines, so if `self` contains [`Newline::CrLf`], a CR LF pair in `s`
+    /// counts as an occurrence of `CrLf` but not of
+    /// [`Newline::CarriageReturn`] or [`Newline::LineFeed`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet};
+    ///
+    /// let s = "foo\r\nbar\nbaz\u{2028}";
+    /// assert_eq!(
+    ///     NewlineSet::ASCII.present_in(s),
+    ///     Newline::LineFeed | Newline::CrLf,
+    /// );
+    /// assert_eq!(
+    ///     NewlineSet::UNICODE.present_in(s),
+    ///     Newline::LineFeed | Newline::CrLf | Newline::LineSeparator,
+    /// );
+    /// assert_eq!(
+    ///     NewlineSet::from(Newline::LineFeed).present_in(s),
+    ///     Newline::LineFeed.into(),
+    /// );
+    /// ```
+    pub fn present_in(&self, s: &str) -> NewlineSet {
+        let mut present = NewlineSet::new();
+        let mut pos = 0;
+        while present != *self {
+            let Some((start, end)) = self.search(&s[pos..]) else {
+                break;
+            };
+            present.insert(matched_newline(s, pos + start, pos + end));
+            pos += end;
+        }
+        present
+    }
 }
 
 impl Ord for NewlineSet {
@@ -1175,6 +1371,14 @@ mod tests {
             );
         }
 
+        #[test]
+        fn lsp() {
+            assert_eq!(
+                NewlineSet::LSP,
+                NewlineSet::from([Newline::LineFeed, Newline::CarriageReturn, Newline::CrLf])
+            );
+        }
+
         #[test]
         fn nlf() {
             assert_eq!(
@@ -1753,6 +1957,18 @@ mod tests {
         assert_eq!(!nlset, comp);
     }
 
+    #[rstest]
+    #[case(NewlineSet::UNICODE, "", vec![])]
+    #[case(NewlineSet::UNICODE, "foobar", vec![])]
+    #[case(NewlineSet::UNICODE, "foo\r\nbar\r\n", vec![Newline::CrLf])]
+    #[case(NewlineSet::UNICODE, "foo\r\n\rbar\n", vec![Newline::LineFeed, Newline::CarriageReturn, Newline::CrLf])]
+    #[case(NewlineSet::RUST, "foo\rbar", vec![])]
+    #[case(Newline::CarriageReturn | Newline::LineFeed, "foo\r\n", vec![Newline::LineFeed, Newline::CarriageReturn])]
+    #[case(NewlineSet::UNICODE, "\x0B\x0C\u{0085}\u{2028}\u{2029}", vec![Newline::VerticalTab, Newline::FormFeed, Newline::NextLine, Newline::LineSeparator, Newline::ParagraphSeparator])]
+    fn test_present_in(#[case] nlset: NewlineSet, #[case] s: &str, #[case] present: Vec<Newline>) {
+        assert_eq!(nlset.present_in(s), NewlineSet::from_iter(present));
+    }
+
     #[test]
     fn not_newline() {
         for nl in Newline::iter() {
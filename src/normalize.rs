@@ -0,0 +1,282 @@
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use crate::pattern::NewlinePattern;
+use std::borrow::Cow;
+
+/// Replace every newline sequence in `s` that is in the [`NewlineSet`] `from`
+/// with the newline sequence `to`.
+///
+/// Newline sequences are located with [`NewlinePattern::search()`], so if
+/// `from` contains [`Newline::CrLf`], each CR LF pair is replaced as a single
+/// unit rather than as a CR and an LF.
+///
+/// If `s` does not contain any newline sequences that need to be replaced, it
+/// is returned as-is without allocating.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{normalize, Newline, NewlineSet};
+/// use std::borrow::Cow;
+///
+/// assert_eq!(
+///     normalize("foo\r\nbar\rbaz\u{2028}", NewlineSet::UNICODE, Newline::LineFeed),
+///     "foo\nbar\nbaz\n",
+/// );
+/// assert!(matches!(
+///     normalize("foo\nbar\n", NewlineSet::UNICODE, Newline::LineFeed),
+///     Cow::Borrowed(_),
+/// ));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn normalize(s: &str, from: NewlineSet, to: Newline) -> Cow<'_, str> {
+    let mut buf: Option<String> = None;
+    // The end of the portion of `s` that has been copied into `buf`
+    let mut copied = 0;
+    let mut pos = 0;
+    while let Some((start, end)) = from.search(&s[pos..]) {
+        let start = pos + start;
+        let end = pos + end;
+        if &s[start..end] != to.as_str() {
+            let buf = buf.get_or_insert_with(|| String::with_capacity(s.len()));
+            buf.push_str(&s[copied..start]);
+            buf.push_str(to.as_str());
+            copied = end;
+        }
+        pos = end;
+    }
+    match buf {
+        Some(mut buf) => {
+            buf.push_str(&s[copied..]);
+            Cow::Owned(buf)
+        }
+        None => Cow::Borrowed(s),
+    }
+}
+
+/// Replace every UTF-8-encoded newline sequence in `s` that is in the
+/// [`NewlineSet`] `from` with the UTF-8 encoding of the newline sequence `to`.
+///
+/// This is the byte-slice counterpart of [`normalize()`].  `s` does not need
+/// to be valid UTF-8.  If `s` does not contain any newline sequences that need
+/// to be replaced, it is returned as-is without allocating.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{normalize_bytes, Newline, NewlineSet};
+///
+/// assert_eq!(
+///     normalize_bytes(b"foo\r\n\xFFbar\r", NewlineSet::ASCII, Newline::LineFeed),
+///     &b"foo\n\xFFbar\n"[..],
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn normalize_bytes(s: &[u8], from: NewlineSet, to: Newline) -> Cow<'_, [u8]> {
+    normalize_bytes_with(s, &from, to.as_str().as_bytes())
+}
+
+/// Replace every newline sequence in `s` matched by `from` (using
+/// [`NewlinePattern::search_in()`]) with `to_bytes`
+pub(crate) fn normalize_bytes_with<'a, P: NewlinePattern>(
+    s: &'a [u8],
+    from: &P,
+    to_bytes: &[u8],
+) -> Cow<'a, [u8]> {
+    let mut buf: Option<Vec<u8>> = None;
+    // The end of the portion of `s` that has been copied into `buf`
+    let mut copied = 0;
+    let mut pos = 0;
+    while let Some((start, end)) = from.search_in(&s[pos..]) {
+        let start = pos + start;
+        let end = pos + end;
+        if &s[start..end] != to_bytes {
+            let buf = buf.get_or_insert_with(|| Vec::with_capacity(s.len()));
+            buf.extend_from_slice(&s[copied..start]);
+            buf.extend_from_slice(to_bytes);
+            copied = end;
+        }
+        pos = end;
+    }
+    match buf {
+        Some(mut buf) => {
+            buf.extend_from_slice(&s[copied..]);
+            Cow::Owned(buf)
+        }
+        None => Cow::Borrowed(s),
+    }
+}
+
+/// Replace every newline sequence in `s` that is in the [`NewlineSet`] `from`
+/// with the newline sequence `to`, modifying `s` in place.
+///
+/// This produces the same result as [`normalize()`], but it reuses `s`'s
+/// buffer rather than allocating a new string.  If no newline sequence in
+/// `from` is shorter than `to` (e.g., when converting CR LF to LF or U+2028 to
+/// LF), the conversion is done in a single compacting pass over the string.
+/// Otherwise, the string is first scanned to determine how much additional
+/// capacity is needed, and the buffer is grown at most once.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{normalize_in_place, Newline, NewlineSet};
+///
+/// let mut s = String::from("foo\r\nbar\r\n");
+/// normalize_in_place(&mut s, NewlineSet::ASCII, Newline::LineFeed);
+/// assert_eq!(s, "foo\nbar\n");
+/// normalize_in_place(&mut s, NewlineSet::ASCII, Newline::CrLf);
+/// assert_eq!(s, "foo\r\nbar\r\n");
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn normalize_in_place(s: &mut String, from: NewlineSet, to: Newline) {
+    let mut buf = core::mem::take(s).into_bytes();
+    normalize_bytes_in_place(&mut buf, from, to);
+    let Ok(normed) = String::from_utf8(buf) else {
+        unreachable!("Replacing complete newline sequences should preserve UTF-8 validity");
+    };
+    *s = normed;
+}
+
+/// Replace every UTF-8-encoded newline sequence in `buf` that is in the
+/// [`NewlineSet`] `from` with the UTF-8 encoding of the newline sequence
+/// `to`, modifying `buf` in place.
+///
+/// `buf` need not be valid UTF-8; invalid sequences are left as-is.
+///
+/// If no newline sequence in `from` is shorter than `to`, the conversion is
+/// done in a single compacting pass over the buffer.  Otherwise, the buffer
+/// is first scanned to determine how much additional capacity is needed, and
+/// it is grown at most once.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{normalize_bytes_in_place, Newline, NewlineSet};
+///
+/// let mut buf = b"foo\xE2\x80\xA8bar\xFF\r".to_vec();
+/// normalize_bytes_in_place(&mut buf, NewlineSet::UNICODE, Newline::CrLf);
+/// assert_eq!(buf, b"foo\r\nbar\xFF\r\n");
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn normalize_bytes_in_place(buf: &mut Vec<u8>, from: NewlineSet, to: Newline) {
+    let compacting = from.iter().all(|nl| nl.len_utf8() >= to.len_utf8());
+    normalize_bytes_in_place_with(buf, &from, to.as_str().as_bytes(), compacting);
+}
+
+/// Replace every newline sequence in `buf` matched by `from` (using
+/// [`NewlinePattern::search_in()`]) with `to_bytes`, modifying `buf` in
+/// place.  `compacting` must be true only if no sequence matched by `from` is
+/// shorter than `to_bytes`.
+pub(crate) fn normalize_bytes_in_place_with<P: NewlinePattern>(
+    buf: &mut Vec<u8>,
+    from: &P,
+    to_bytes: &[u8],
+    compacting: bool,
+) {
+    // In order for output to be written into the same buffer that input is
+    // read from, the unread input is first shifted right by the largest amount
+    // that the output ever gets ahead of the input.
+    let shift = if compacting {
+        0
+    } else {
+        let mut shift = 0;
+        let mut in_pos = 0;
+        let mut out_pos = 0;
+        while let Some((start, end)) = from.search_in(&buf[in_pos..]) {
+            out_pos += start + to_bytes.len();
+            in_pos += end;
+            shift = shift.max(out_pos.saturating_sub(in_pos));
+        }
+        shift
+    };
+    let len = buf.len();
+    if shift > 0 {
+        buf.resize(len + shift, 0);
+        buf.copy_within(0..len, shift);
+    }
+    let end = buf.len();
+    let mut r = shift;
+    let mut w = 0;
+    while let Some((start, nl_end)) = from.search_in(&buf[r..end]) {
+        buf.copy_within(r..(r + start), w);
+        w += start;
+        buf[w..(w + to_bytes.len())].copy_from_slice(to_bytes);
+        w += to_bytes.len();
+        r += nl_end;
+    }
+    buf.copy_within(r..end, w);
+    w += end - r;
+    buf.truncate(w);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", NewlineSet::UNICODE, Newline::LineFeed, "")]
+    #[case("foo\r\nbar\r\n", NewlineSet::ASCII, Newline::LineFeed, "foo\nbar\n")]
+    #[case("foo\nbar\rbaz", NewlineSet::ASCII, Newline::CrLf, "foo\r\nbar\r\nbaz")]
+    #[case("foo\r\n\r\n", NewlineSet::ASCII, Newline::CrLf, "foo\r\n\r\n")]
+    #[case("foo\r\n", Newline::LineFeed.into(), Newline::CrLf, "foo\r\r\n")]
+    #[case("foo\r\n", Newline::CarriageReturn.into(), Newline::LineFeed, "foo\n\n")]
+    #[case(
+        "foo\r\r\n\n",
+        NewlineSet::ASCII,
+        Newline::LineSeparator,
+        "foo\u{2028}\u{2028}\u{2028}"
+    )]
+    #[case(
+        "a\u{0085}b\x0Bc\x0Cd\u{2029}",
+        NewlineSet::UNICODE,
+        Newline::LineFeed,
+        "a\nb\nc\nd\n"
+    )]
+    #[case("a\u{0085}b\x0Bc", NewlineSet::NLF, Newline::CrLf, "a\r\nb\x0Bc")]
+    #[case("foo\nbar", NewlineSet::EMPTY, Newline::CrLf, "foo\nbar")]
+    fn test_normalize(
+        #[case] s: &str,
+        #[case] from: NewlineSet,
+        #[case] to: Newline,
+        #[case] expected: &str,
+    ) {
+        let r = normalize(s, from, to);
+        assert_eq!(r, expected);
+        assert_eq!(matches!(r, Cow::Borrowed(_)), s == expected);
+        let r = normalize_bytes(s.as_bytes(), from, to);
+        assert_eq!(r, expected.as_bytes());
+        assert_eq!(matches!(r, Cow::Borrowed(_)), s == expected);
+    }
+
+    #[test]
+    fn in_place_matches_normalize() {
+        let sets = [
+            NewlineSet::RUST,
+            NewlineSet::ASCII,
+            NewlineSet::UNICODE,
+            Newline::LineFeed.into(),
+            Newline::CarriageReturn.into(),
+            Newline::LineFeed | Newline::LineSeparator,
+            Newline::CarriageReturn | Newline::NextLine,
+        ];
+        for len in 0..=4 {
+            for chars in core::iter::repeat(['a', '\r', '\n', '\u{0085}', '\u{2028}'])
+                .take(len)
+                .multi_cartesian_product()
+            {
+                let s = chars.into_iter().collect::<String>();
+                for from in sets {
+                    for to in Newline::iter() {
+                        let expected = normalize(&s, from, to);
+                        let mut s2 = s.clone();
+                        normalize_in_place(&mut s2, from, to);
+                        assert_eq!(s2, expected, "s={s:?}, from={from:?}, to={to:?}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,18 +1,68 @@
+use crate::haystack::Haystack;
 use crate::nl::Newline;
 use crate::nlset::NewlineSet;
 
 mod private {
+    use crate::encoding::EncodedNewlineSet;
+
     #[allow(unnameable_types)]
-    pub trait Sealed {}
+    pub trait Sealed {
+        /// Returns the newline sequences matched by the pattern together with
+        /// the encoding in which they are searched for in byte haystacks
+        fn encoded(&self) -> EncodedNewlineSet;
+    }
+
+    impl Sealed for super::Newline {
+        fn encoded(&self) -> EncodedNewlineSet {
+            EncodedNewlineSet::utf8((*self).into())
+        }
+    }
 
-    impl Sealed for super::Newline {}
+    impl Sealed for super::NewlineSet {
+        fn encoded(&self) -> EncodedNewlineSet {
+            EncodedNewlineSet::utf8(*self)
+        }
+    }
 
-    impl Sealed for super::NewlineSet {}
+    impl Sealed for EncodedNewlineSet {
+        fn encoded(&self) -> EncodedNewlineSet {
+            *self
+        }
+    }
 }
 
 pub trait NewlinePattern: private::Sealed {
     fn search(&self, s: &str) -> Option<(usize, usize)>;
     fn rsearch(&self, s: &str) -> Option<(usize, usize)>;
+
+    /// Like [`search()`][NewlinePattern::search], but searches any
+    /// [`Haystack`].  Byte slices are searched for the UTF-8 encodings of the
+    /// newline sequences (or, for an
+    /// [`EncodedNewlineSet`][crate::EncodedNewlineSet], their encodings in
+    /// its [`ByteEncoding`][crate::ByteEncoding]) and need not be valid
+    /// UTF-8, while `[u16]` slices are searched for the UTF-16 encodings and
+    /// may contain unpaired surrogates.  The returned offsets are measured in
+    /// units of the haystack's elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlinePattern, NewlineSet};
+    ///
+    /// assert_eq!(Newline::CrLf.search_in(&b"\r\r\n\xFF"[..]), Some((1, 3)));
+    /// let s: Vec<u16> = "foo\u{2028}".encode_utf16().collect();
+    /// assert_eq!(NewlineSet::UNICODE.search_in(&s[..]), Some((3, 4)));
+    /// ```
+    fn search_in<H: Haystack + ?Sized>(&self, haystack: &H) -> Option<(usize, usize)> {
+        haystack.search_pattern(self)
+    }
+
+    /// Like [`rsearch()`][NewlinePattern::rsearch], but searches any
+    /// [`Haystack`] in the same manner as
+    /// [`search_in()`][NewlinePattern::search_in]
+    fn rsearch_in<H: Haystack + ?Sized>(&self, haystack: &H) -> Option<(usize, usize)> {
+        haystack.rsearch_pattern(self)
+    }
 }
 
 impl NewlinePattern for Newline {
@@ -34,19 +84,26 @@ impl NewlinePattern for NewlineSet {
         if self.is_empty() {
             return None;
         }
-        let start = s.find(self.pattern())?;
-        let length = if self.crlf && s[start..].starts_with("\r\n") {
-            2
-        } else {
-            let Some(ch) = s[start..].chars().next() else {
-                unreachable!(
-                    "Nonempty NewlineSet pattern should have matched at start of a character"
-                )
+        let mut s_start = 0;
+        loop {
+            let start = s_start + s[s_start..].find(self.pattern())?;
+            let length = if self.crlf && s[start..].starts_with("\r\n") {
+                2
+            } else {
+                let Some(ch) = s[start..].chars().next() else {
+                    unreachable!(
+                        "Nonempty NewlineSet pattern should have matched at start of a character"
+                    )
+                };
+                if !self.cr && ch == '\r' {
+                    s_start = start + 1;
+                    continue;
+                }
+                ch.len_utf8()
             };
-            ch.len_utf8()
-        };
-        let end = start.saturating_add(length);
-        Some((start, end))
+            let end = start.saturating_add(length);
+            return Some((start, end));
+        }
     }
 
     fn rsearch(&self, s: &str) -> Option<(usize, usize)> {
@@ -81,6 +138,41 @@ impl NewlinePattern for NewlineSet {
     }
 }
 
+/// Returns the `Newline` for a newline sequence matched at `s[start..end]` by
+/// a [`NewlinePattern`]
+pub(crate) fn matched_newline(s: &str, start: usize, end: usize) -> Newline {
+    let Ok(nl) = Newline::try_from(&s[start..end]) else {
+        unreachable!("NewlinePattern should only match newline sequences");
+    };
+    nl
+}
+
+/// Splits `s` on the first newline sequence matched by `pattern`
+pub(crate) fn split_once<'a, P: NewlinePattern + ?Sized, H: Haystack + ?Sized>(
+    pattern: &P,
+    s: &'a H,
+) -> Option<(&'a H, Newline, &'a H)> {
+    let (start, end) = s.search_pattern(pattern)?;
+    Some(split_at_match(s, start, end))
+}
+
+/// Splits `s` on the last newline sequence matched by `pattern`
+pub(crate) fn rsplit_once<'a, P: NewlinePattern + ?Sized, H: Haystack + ?Sized>(
+    pattern: &P,
+    s: &'a H,
+) -> Option<(&'a H, Newline, &'a H)> {
+    let (start, end) = s.rsearch_pattern(pattern)?;
+    Some(split_at_match(s, start, end))
+}
+
+fn split_at_match<H: Haystack + ?Sized>(s: &H, start: usize, end: usize) -> (&H, Newline, &H) {
+    (
+        s.slice(0, start),
+        s.matched_newline(start, end),
+        s.slice(end, s.haystack_len()),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +238,9 @@ mod tests {
         #[case(Newline::LineSeparator.into(), "foo\u{2028}bar", Some((3, 6)))]
         #[case(Newline::LineFeed | Newline::CarriageReturn, "foo\rbar\nquux", Some((3, 4)))]
         #[case(Newline::LineFeed | Newline::CrLf, "foo\r\nbar", Some((3, 5)))]
+        #[case(Newline::LineFeed | Newline::CrLf, "foo\rbar", None)]
+        #[case(Newline::LineFeed | Newline::CrLf, "foo\rbar\nquux", Some((7, 8)))]
+        #[case(Newline::CrLf.into(), "foo\rbar\r\nquux", Some((7, 9)))]
         fn search(#[case] nlset: NewlineSet, #[case] s: &str, #[case] m: Option<(usize, usize)>) {
             assert_eq!(nlset.search(s), m);
             if let Some((start, end)) = m {
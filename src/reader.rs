@@ -0,0 +1,181 @@
+use crate::bytes::Converter;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use std::io::{self, BufRead, Read};
+
+/// The number of bytes to request from the inner reader at a time
+const CHUNK_SIZE: usize = 8192;
+
+/// A reader adapter that converts newline sequences as data is read.
+///
+/// A `NewlineReader` wraps another [`Read`] value and replaces every
+/// UTF-8-encoded newline sequence in a source [`NewlineSet`] with a target
+/// [`Newline`] in the data read from it.  The data need not be valid UTF-8.
+///
+/// Newline sequences that are split across multiple reads from the inner
+/// reader — such as a CR LF pair whose CR ends one read and whose LF starts
+/// the next, or a multi-byte sequence like U+0085 or U+2028 — are still
+/// converted as a single unit.
+///
+/// `NewlineReader` also implements [`BufRead`], with the buffer containing
+/// converted data.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineReader, NewlineSet};
+/// use std::io::Read;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let src = "foo\r\nbar\u{2028}baz\r".as_bytes();
+/// let mut reader = NewlineReader::new(src, NewlineSet::UNICODE, Newline::LineFeed);
+/// let mut s = String::new();
+/// reader.read_to_string(&mut s)?;
+/// assert_eq!(s, "foo\nbar\nbaz\n");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+pub struct NewlineReader<R> {
+    inner: R,
+    converter: Converter,
+
+    /// Raw data read from `inner`
+    raw: Vec<u8>,
+
+    /// Converted data
+    out: Vec<u8>,
+
+    /// The position in `out` of the first byte that has not been consumed
+    pos: usize,
+
+    /// Whether `inner` has reached end of file
+    eof: bool,
+}
+
+impl<R: Read> NewlineReader<R> {
+    /// Create a new `NewlineReader` that reads from `inner` and converts the
+    /// newline sequences in `from` to `to`
+    pub fn new(inner: R, from: NewlineSet, to: Newline) -> NewlineReader<R> {
+        NewlineReader {
+            inner,
+            converter: Converter::new(from, to),
+            raw: Vec::new(),
+            out: Vec::new(),
+            pos: 0,
+            eof: false,
+        }
+    }
+
+    /// Returns a reference to the inner reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader may cause data to be lost.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Return the inner reader.
+    ///
+    /// Any data that has been read from the inner reader but not yet read
+    /// from the `NewlineReader` is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for NewlineReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let avail = self.fill_buf()?;
+        let n = avail.len().min(buf.len());
+        buf[..n].copy_from_slice(&avail[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read> BufRead for NewlineReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos >= self.out.len() && !self.eof {
+            self.out.clear();
+            self.pos = 0;
+            self.raw.resize(CHUNK_SIZE, 0);
+            let n = match self.inner.read(&mut self.raw) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if n == 0 {
+                self.eof = true;
+            }
+            self.converter
+                .convert(&self.raw[..n], self.eof, &mut self.out);
+        }
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = self.out.len().min(self.pos + amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::normalize;
+
+    /// A reader that returns at most `chunk_size` bytes per read
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk_size: usize,
+    }
+
+    impl Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.data.len().min(buf.len()).min(self.chunk_size);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn chunked_reads() {
+        let s = "foo\r\nbar\rbaz\n\u{2028}\u{0085}\u{2029}\r\r\n\n\r";
+        for from in [NewlineSet::RUST, NewlineSet::ASCII, NewlineSet::UNICODE] {
+            for to in Newline::iter() {
+                let expected = normalize(s, from, to);
+                for chunk_size in 1..=4 {
+                    let inner = ChunkedReader {
+                        data: s.as_bytes(),
+                        chunk_size,
+                    };
+                    let mut reader = NewlineReader::new(inner, from, to);
+                    let mut buf = Vec::new();
+                    reader.read_to_end(&mut buf).unwrap();
+                    assert_eq!(
+                        buf,
+                        expected.as_bytes(),
+                        "from={from:?}, to={to:?}, chunk_size={chunk_size}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bufread_lines() {
+        let inner = ChunkedReader {
+            data: b"foo\rbar\r\nbaz\x85\xC2\x85quux",
+            chunk_size: 3,
+        };
+        let reader = NewlineReader::new(inner, NewlineSet::NLF, Newline::LineFeed);
+        let lines = reader.split(b'\n').collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(lines, [&b"foo"[..], b"bar", b"baz\x85", b"quux"]);
+    }
+}
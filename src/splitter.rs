@@ -0,0 +1,183 @@
+use crate::iter::SplitLines;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+
+/// A configurable splitter for breaking strings into lines on the newline
+/// sequences in a [`NewlineSet`]
+///
+/// A `LineSplitter` is constructed with [`LineSplitter::new()`] and then
+/// configured using its builder methods.  Calling [`LineSplitter::split()`]
+/// then returns an iterator over the lines of a string.
+///
+/// The default configuration splits the same way as
+/// [`NewlineSet::lines()`]: newline sequences are not included in the lines,
+/// empty lines are yielded, and a newline sequence at the end of the string
+/// does not produce a trailing empty line.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, LineSplitter};
+///
+/// let splitter = LineSplitter::new(Newline::LineFeed | Newline::CarriageReturn)
+///     .keep_newlines(true)
+///     .skip_empty(true)
+///     .cr_swallows_lf(true);
+/// let mut iter = splitter.split("foo\r\n\nbar\rbaz\n");
+/// assert_eq!(iter.next(), Some("foo\r\n"));
+/// assert_eq!(iter.next(), Some("bar\r"));
+/// assert_eq!(iter.next(), Some("baz\n"));
+/// assert_eq!(iter.next(), None);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LineSplitter {
+    nlset: NewlineSet,
+    keep_newlines: bool,
+    skip_empty: bool,
+    trailing_empty: bool,
+    cr_swallows_lf: bool,
+}
+
+impl LineSplitter {
+    /// Create a new `LineSplitter` that splits on the newline sequences in
+    /// `nlset`, using the default configuration
+    pub fn new(nlset: NewlineSet) -> LineSplitter {
+        LineSplitter {
+            nlset,
+            keep_newlines: false,
+            skip_empty: false,
+            trailing_empty: false,
+            cr_swallows_lf: false,
+        }
+    }
+
+    /// Set whether the yielded lines include their terminating newline
+    /// sequences.  The default is `false`.
+    pub fn keep_newlines(mut self, flag: bool) -> LineSplitter {
+        self.keep_newlines = flag;
+        self
+    }
+
+    /// Set whether lines with no content (other than a newline sequence) are
+    /// skipped.  The default is `false`.
+    pub fn skip_empty(mut self, flag: bool) -> LineSplitter {
+        self.skip_empty = flag;
+        self
+    }
+
+    /// Set whether a newline sequence at the end of the string produces a
+    /// trailing empty line, as with [`str::split()`], rather than being
+    /// treated as a terminator for the final line, as with
+    /// [`str::split_terminator()`].  The default is `false`.
+    ///
+    /// This setting has no effect if [`skip_empty()`][Self::skip_empty] is
+    /// enabled.
+    pub fn trailing_empty(mut self, flag: bool) -> LineSplitter {
+        self.trailing_empty = flag;
+        self
+    }
+
+    /// Set whether a carriage return that is immediately followed by a line
+    /// feed is treated as a single newline sequence even if the
+    /// `NewlineSet` does not contain [`Newline::CrLf`].  The default is
+    /// `false`.
+    ///
+    /// When enabled and the `NewlineSet` contains
+    /// [`Newline::CarriageReturn`], a CR LF pair ends a single line, and the
+    /// LF is not treated as part of the following line's content (nor, if
+    /// the set contains [`Newline::LineFeed`], as an additional line break).
+    /// A `NewlineSet` that contains [`Newline::CrLf`] always treats CR LF as
+    /// a single newline sequence.
+    pub fn cr_swallows_lf(mut self, flag: bool) -> LineSplitter {
+        self.cr_swallows_lf = flag;
+        self
+    }
+
+    /// Returns the `NewlineSet` that the splitter splits on
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Returns an iterator over the lines of `s` as split according to the
+    /// splitter's configuration
+    pub fn split<'a>(&self, s: &'a str) -> SplitLines<'a> {
+        let mut nlset = self.nlset;
+        if self.cr_swallows_lf && nlset.contains(Newline::CarriageReturn) {
+            nlset.insert(Newline::CrLf);
+        }
+        SplitLines::new(
+            nlset,
+            s,
+            self.keep_newlines,
+            self.skip_empty,
+            self.trailing_empty,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(false, false, false, false, vec!["foo", "", "bar", "", "baz"])]
+    #[case(true, false, false, false, vec!["foo\r", "\n", "bar\n", "\n", "baz\r"])]
+    #[case(false, true, false, false, vec!["foo", "bar", "baz"])]
+    #[case(false, false, true, false, vec!["foo", "", "bar", "", "baz", ""])]
+    #[case(false, false, false, true, vec!["foo", "bar", "", "baz"])]
+    #[case(true, false, true, true, vec!["foo\r\n", "bar\n", "\n", "baz\r", ""])]
+    #[case(true, true, true, true, vec!["foo\r\n", "bar\n", "baz\r"])]
+    fn split(
+        #[case] keep_newlines: bool,
+        #[case] skip_empty: bool,
+        #[case] trailing_empty: bool,
+        #[case] cr_swallows_lf: bool,
+        #[case] mut lines: Vec<&str>,
+    ) {
+        let splitter = LineSplitter::new(Newline::LineFeed | Newline::CarriageReturn)
+            .keep_newlines(keep_newlines)
+            .skip_empty(skip_empty)
+            .trailing_empty(trailing_empty)
+            .cr_swallows_lf(cr_swallows_lf);
+        let s = "foo\r\nbar\n\nbaz\r";
+        assert_eq!(splitter.split(s).collect_vec(), lines);
+        lines.reverse();
+        assert_eq!(splitter.split(s).rev().collect_vec(), lines);
+    }
+
+    #[test]
+    fn default_matches_lines() {
+        let s = "foo\r\nbar\n\n\rbaz\u{2028}\r";
+        for nlset in [NewlineSet::RUST, NewlineSet::ASCII, NewlineSet::UNICODE] {
+            assert_eq!(
+                LineSplitter::new(nlset).split(s).collect_vec(),
+                nlset.lines(s).collect_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn cr_only_swallows_lf() {
+        let splitter = LineSplitter::new(Newline::CarriageReturn.into()).cr_swallows_lf(true);
+        assert_eq!(
+            splitter.split("foo\r\nbar\rbaz\n").collect_vec(),
+            ["foo", "bar", "baz\n"]
+        );
+        let splitter = splitter.cr_swallows_lf(false);
+        assert_eq!(
+            splitter.split("foo\r\nbar\rbaz\n").collect_vec(),
+            ["foo", "\nbar", "baz\n"]
+        );
+    }
+
+    #[test]
+    fn swallowing_without_cr() {
+        let splitter = LineSplitter::new(Newline::LineFeed.into()).cr_swallows_lf(true);
+        assert_eq!(
+            splitter.split("foo\r\nbar\rbaz").collect_vec(),
+            ["foo\r", "bar\rbaz"]
+        );
+    }
+}
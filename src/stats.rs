@@ -0,0 +1,404 @@
+use crate::bytes::{newline_prefix, partial_newline_len, search_bytes};
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use core::ops::Index;
+
+/// Statistics on the newline sequences in a piece of text that is supplied
+/// in chunks.
+///
+/// A `NewlineStats` is created for a given [`NewlineSet`], after which
+/// successive chunks of input are passed to [`feed()`][NewlineStats::feed]
+/// or [`feed_bytes()`][NewlineStats::feed_bytes].  A newline sequence that
+/// straddles two chunks (such as a CR LF pair split between the end of one
+/// chunk and the start of the next) is attributed correctly; as a
+/// consequence, a CR at the end of the input fed so far is not counted until
+/// the next byte is seen or [`finish()`][NewlineStats::finish] is called.
+///
+/// Byte input does not need to be valid UTF-8; newline sequences are located
+/// by searching for their UTF-8 encodings.  All offsets are measured in
+/// bytes from the start of the input.
+///
+/// The number of occurrences of each [`Newline`] can be retrieved by indexing
+/// a `NewlineStats` with the `Newline`.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineSet, NewlineStats};
+///
+/// let mut stats = NewlineStats::new(NewlineSet::UNICODE);
+/// stats.feed("foo\r");
+/// stats.feed("\nquux\n\r");
+/// stats.feed("\n");
+/// stats.finish();
+/// assert_eq!(stats[Newline::CrLf], 2);
+/// assert_eq!(stats[Newline::LineFeed], 1);
+/// assert_eq!(stats[Newline::CarriageReturn], 0);
+/// assert_eq!(stats.first_offset(Newline::CrLf), Some(3));
+/// assert_eq!(stats.last_offset(Newline::CrLf), Some(10));
+/// assert_eq!(stats.longest_line(), Some((5, 9)));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NewlineStats {
+    nlset: NewlineSet,
+    counts: [u64; Newline::COUNT],
+    first: [Option<u64>; Newline::COUNT],
+    last: [Option<u64>; Newline::COUNT],
+
+    /// Start & end offsets of the content of the longest line terminated so
+    /// far
+    longest: Option<(u64, u64)>,
+
+    /// Offset of the start of the current line
+    line_start: u64,
+
+    /// Total number of bytes fed so far, including `pending`
+    len: u64,
+
+    /// Bytes at the end of the input fed so far that may be the start of a
+    /// newline sequence
+    pending: [u8; 2],
+    pending_len: usize,
+}
+
+impl NewlineStats {
+    /// Create a new `NewlineStats` that counts the newline sequences in
+    /// `nlset`
+    pub fn new(nlset: NewlineSet) -> NewlineStats {
+        NewlineStats {
+            nlset,
+            counts: [0; Newline::COUNT],
+            first: [None; Newline::COUNT],
+            last: [None; Newline::COUNT],
+            longest: None,
+            line_start: 0,
+            len: 0,
+            pending: [0; 2],
+            pending_len: 0,
+        }
+    }
+
+    /// Returns the set of newline sequences being counted
+    pub fn newline_set(&self) -> NewlineSet {
+        self.nlset
+    }
+
+    /// Process the next chunk of input
+    pub fn feed(&mut self, s: &str) {
+        self.feed_bytes(s.as_bytes());
+    }
+
+    /// Process the next chunk of input as bytes
+    pub fn feed_bytes(&mut self, mut chunk: &[u8]) {
+        if self.pending_len > 0 {
+            let p = self.pending_len;
+            let take = chunk.len().min(3 - p);
+            let mut buf = [0u8; 3];
+            buf[..p].copy_from_slice(&self.pending[..p]);
+            buf[p..(p + take)].copy_from_slice(&chunk[..take]);
+            let window = &buf[..(p + take)];
+            if take == chunk.len() && partial_newline_len(self.nlset, window) == window.len() {
+                self.set_pending(window);
+                self.len += chunk.len() as u64;
+                return;
+            }
+            self.pending_len = 0;
+            let at = self.len - p as u64;
+            if let Some(nl) = newline_prefix(self.nlset, window) {
+                self.record(nl, at);
+                let used = nl.len_utf8() - p;
+                self.len += used as u64;
+                chunk = &chunk[used..];
+            }
+        }
+        let keep = partial_newline_len(self.nlset, chunk);
+        let (complete, rest) = chunk.split_at(chunk.len() - keep);
+        let base = self.len;
+        let mut pos = 0;
+        while let Some((start, end)) = search_bytes(self.nlset, &complete[pos..]) {
+            let Some(nl) = newline_prefix(self.nlset, &complete[(pos + start)..]) else {
+                unreachable!("search_bytes() should only return newline positions");
+            };
+            self.record(nl, base + (pos + start) as u64);
+            pos += end;
+        }
+        self.set_pending(rest);
+        self.len += chunk.len() as u64;
+    }
+
+    /// Declare that the end of the input has been reached, causing any
+    /// trailing bytes held back from previous chunks to be resolved
+    pub fn finish(&mut self) {
+        let p = self.pending_len;
+        if p > 0 {
+            self.pending_len = 0;
+            if let Some(nl) = newline_prefix(self.nlset, &self.pending[..p]) {
+                self.record(nl, self.len - p as u64);
+            }
+        }
+    }
+
+    /// Combine `other`, containing statistics for the input immediately
+    /// following that fed to `self`, into `self`.
+    ///
+    /// This allows large inputs to be split into chunks that are processed
+    /// in parallel.  Any bytes held back at the end of `self`'s input are
+    /// resolved as though [`finish()`][NewlineStats::finish] had been called,
+    /// so the point at which the input is split must not fall within a
+    /// newline sequence (including between the CR and LF of a CR LF pair).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` do not have the same
+    /// [`NewlineSet`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use newlines::{Newline, NewlineSet, NewlineStats};
+    ///
+    /// let mut left = NewlineStats::new(NewlineSet::ASCII);
+    /// left.feed("foo\r\nbar\n");
+    /// let mut right = NewlineStats::new(NewlineSet::ASCII);
+    /// right.feed("baz\r\nquux");
+    /// right.finish();
+    /// left.merge(&right);
+    /// assert_eq!(left[Newline::CrLf], 2);
+    /// assert_eq!(left.last_offset(Newline::CrLf), Some(12));
+    /// assert_eq!(left.longest_line(), Some((14, 18)));
+    /// ```
+    pub fn merge(&mut self, other: &NewlineStats) {
+        assert_eq!(
+            self.nlset, other.nlset,
+            "Cannot merge NewlineStats for different NewlineSets"
+        );
+        self.finish();
+        let offset = self.len;
+        let first_break = other.first.iter().flatten().min().copied();
+        if let Some(first_break) = first_break {
+            self.end_line(offset + first_break);
+            if let Some((start, end)) = other.longest {
+                self.update_longest(offset + start, offset + end);
+            }
+            self.line_start = offset + other.line_start;
+        }
+        for i in 0..Newline::COUNT {
+            self.counts[i] += other.counts[i];
+            if self.first[i].is_none() {
+                self.first[i] = other.first[i].map(|o| offset + o);
+            }
+            if let Some(o) = other.last[i] {
+                self.last[i] = Some(offset + o);
+            }
+        }
+        self.len += other.len;
+        self.set_pending(&other.pending[..other.pending_len]);
+    }
+
+    /// Returns the number of times `nl` has occurred in the input so far.
+    ///
+    /// This is the same as `self[nl]`.
+    pub fn count(&self, nl: Newline) -> u64 {
+        self.counts[nl as usize]
+    }
+
+    /// Returns the total number of newline sequences in the input so far
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the set of newline sequences that have occurred in the input
+    /// so far
+    pub fn present(&self) -> NewlineSet {
+        Newline::iter().filter(|&nl| self.count(nl) > 0).collect()
+    }
+
+    /// Returns the offset of the first occurrence of `nl` in the input so far
+    pub fn first_offset(&self, nl: Newline) -> Option<u64> {
+        self.first[nl as usize]
+    }
+
+    /// Returns the offset of the last occurrence of `nl` in the input so far
+    pub fn last_offset(&self, nl: Newline) -> Option<u64> {
+        self.last[nl as usize]
+    }
+
+    /// Returns the start & end offsets of the content (excluding the
+    /// terminating newline sequence) of the longest line in the input so
+    /// far.  If multiple lines tie for longest, the first one is returned.
+    ///
+    /// Lines are determined the same way as by
+    /// [`NewlineSet::lines()`][crate::NewlineSet::lines], so an empty segment
+    /// after a final newline sequence does not count as a line.  Returns
+    /// `None` if the input is empty.
+    pub fn longest_line(&self) -> Option<(u64, u64)> {
+        let end = self.len - self.pending_len as u64;
+        match self.longest {
+            Some((start, stop)) if stop - start >= end.saturating_sub(self.line_start) => {
+                Some((start, stop))
+            }
+            _ => (end > self.line_start).then_some((self.line_start, end)),
+        }
+    }
+
+    /// Returns the total number of bytes of input fed so far
+    pub fn total_bytes(&self) -> u64 {
+        self.len
+    }
+
+    fn record(&mut self, nl: Newline, at: u64) {
+        let i = nl as usize;
+        self.counts[i] += 1;
+        if self.first[i].is_none() {
+            self.first[i] = Some(at);
+        }
+        self.last[i] = Some(at);
+        self.end_line(at);
+        self.line_start = at + nl.len_utf8() as u64;
+    }
+
+    fn end_line(&mut self, end: u64) {
+        self.update_longest(self.line_start, end);
+    }
+
+    fn update_longest(&mut self, start: u64, end: u64) {
+        if self.longest.map_or(true, |(s, e)| e - s < end - start) {
+            self.longest = Some((start, end));
+        }
+    }
+
+    fn set_pending(&mut self, bytes: &[u8]) {
+        self.pending[..bytes.len()].copy_from_slice(bytes);
+        self.pending_len = bytes.len();
+    }
+}
+
+impl Index<Newline> for NewlineStats {
+    type Output = u64;
+
+    fn index(&self, nl: Newline) -> &u64 {
+        &self.counts[nl as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn expected(nlset: NewlineSet, s: &str) -> NewlineStats {
+        let mut stats = NewlineStats::new(nlset);
+        for line in nlset.lines_with_newlines(s) {
+            let (start, end) = line.content_span();
+            stats.update_longest(start as u64, end as u64);
+            if let (Some(nl), Some((at, _))) = (line.newline(), line.newline_span()) {
+                let i = nl as usize;
+                stats.counts[i] += 1;
+                if stats.first[i].is_none() {
+                    stats.first[i] = Some(at as u64);
+                }
+                stats.last[i] = Some(at as u64);
+            }
+        }
+        stats
+    }
+
+    fn assert_same(stats: &NewlineStats, expected: &NewlineStats, len: usize) {
+        for nl in Newline::iter() {
+            assert_eq!(stats[nl], expected[nl], "count of {nl:?}");
+            assert_eq!(stats.first_offset(nl), expected.first_offset(nl));
+            assert_eq!(stats.last_offset(nl), expected.last_offset(nl));
+        }
+        assert_eq!(stats.longest_line(), expected.longest);
+        assert_eq!(stats.total_bytes(), len as u64);
+    }
+
+    const SAMPLES: [&str; 6] = [
+        "",
+        "foo",
+        "foo\r\nbar\n\rbaz\r",
+        "\r\r\n\n\r\u{2028}\u{0085}\r",
+        "a\u{2029}bb\r\r\ncccc\x0B\x0Cdd\r\n",
+        "\u{2028}\u{2028}x\u{0085}\r\u{2029}\u{2028}yz",
+    ];
+
+    #[rstest]
+    fn two_chunks_match_whole(
+        #[values(NewlineSet::ALL, NewlineSet::ASCII, NewlineSet::RUST, Newline::CarriageReturn | Newline::LineSeparator)]
+        nlset: NewlineSet,
+        #[values(0, 1, 2, 3, 4, 5)] sample: usize,
+    ) {
+        let s = SAMPLES[sample];
+        let want = expected(nlset, s);
+        for i in 0..=s.len() {
+            let (a, b) = s.as_bytes().split_at(i);
+            let mut stats = NewlineStats::new(nlset);
+            stats.feed_bytes(a);
+            stats.feed_bytes(b);
+            stats.finish();
+            assert_same(&stats, &want, s.len());
+        }
+    }
+
+    #[rstest]
+    fn byte_at_a_time(
+        #[values(NewlineSet::ALL, NewlineSet::ASCII, NewlineSet::RUST, Newline::CarriageReturn | Newline::LineSeparator)]
+        nlset: NewlineSet,
+        #[values(0, 1, 2, 3, 4, 5)] sample: usize,
+    ) {
+        let s = SAMPLES[sample];
+        let mut stats = NewlineStats::new(nlset);
+        for b in s.as_bytes() {
+            stats.feed_bytes(core::slice::from_ref(b));
+        }
+        stats.finish();
+        assert_same(&stats, &expected(nlset, s), s.len());
+    }
+
+    #[rstest]
+    fn merge_after_newlines(
+        #[values(NewlineSet::ALL, NewlineSet::ASCII, NewlineSet::RUST)] nlset: NewlineSet,
+        #[values(0, 1, 2, 3, 4, 5)] sample: usize,
+    ) {
+        let s = SAMPLES[sample];
+        let want = expected(nlset, s);
+        let mut splits = vec![0];
+        splits.extend(nlset.lines_with_newlines(s).map(|line| line.span().1));
+        for &i in &splits {
+            let (a, b) = s.split_at(i);
+            let mut left = NewlineStats::new(nlset);
+            left.feed(a);
+            let mut right = NewlineStats::new(nlset);
+            right.feed(b);
+            left.merge(&right);
+            left.finish();
+            assert_same(&left, &want, s.len());
+        }
+    }
+
+    #[test]
+    fn pending_cr_not_counted() {
+        let mut stats = NewlineStats::new(NewlineSet::ASCII);
+        stats.feed("foo\r");
+        assert_eq!(stats[Newline::CarriageReturn], 0);
+        assert_eq!(stats.longest_line(), Some((0, 3)));
+        stats.finish();
+        assert_eq!(stats[Newline::CarriageReturn], 1);
+        assert_eq!(stats.present(), Newline::CarriageReturn.into());
+        assert_eq!(stats.total(), 1);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut stats = NewlineStats::new(NewlineSet::UNICODE);
+        stats.feed_bytes(b"\xFF\xE2\x80\n\xC2");
+        stats.feed_bytes(b"\x85\xE2");
+        stats.feed_bytes(b"\x80\xA9");
+        stats.finish();
+        assert_eq!(stats[Newline::LineFeed], 1);
+        assert_eq!(stats[Newline::NextLine], 1);
+        assert_eq!(stats[Newline::ParagraphSeparator], 1);
+        assert_eq!(stats.last_offset(Newline::ParagraphSeparator), Some(6));
+        assert_eq!(stats.longest_line(), Some((0, 3)));
+    }
+}
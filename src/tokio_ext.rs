@@ -0,0 +1,9 @@
+crate::async_bufread::async_bufread_ext! {
+    feature: "tokio",
+    reader: "tokio::io::AsyncBufRead",
+    trait: tokio::io::AsyncBufRead,
+    ext: TokioAsyncBufReadNewlineExt,
+    read_line_with: TokioReadLineWith,
+    lines: TokioLines,
+    buf_reader: tokio::io::BufReader,
+}
@@ -0,0 +1,49 @@
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+
+/// If `s` starts with the UTF-16 encoding of a newline sequence in `nlset`,
+/// returns that newline sequence.  [`Newline::CrLf`] is preferred over
+/// [`Newline::CarriageReturn`] when both match.
+pub(crate) fn newline_prefix_utf16(nlset: NewlineSet, s: &[u16]) -> Option<Newline> {
+    if nlset.crlf && s.starts_with(&[0x000D, 0x000A]) {
+        return Some(Newline::CrLf);
+    }
+    let nl = match s.first()? {
+        0x000A => Newline::LineFeed,
+        0x000B => Newline::VerticalTab,
+        0x000C => Newline::FormFeed,
+        0x000D => Newline::CarriageReturn,
+        0x0085 => Newline::NextLine,
+        0x2028 => Newline::LineSeparator,
+        0x2029 => Newline::ParagraphSeparator,
+        _ => return None,
+    };
+    nlset.contains(nl).then_some(nl)
+}
+
+/// Returns the start & end indices of the first UTF-16-encoded newline
+/// sequence in `s` that is in `nlset`
+pub(crate) fn search_utf16(nlset: NewlineSet, s: &[u16]) -> Option<(usize, usize)> {
+    if nlset.is_empty() {
+        return None;
+    }
+    (0..s.len()).find_map(|i| {
+        let nl = newline_prefix_utf16(nlset, &s[i..])?;
+        Some((i, i + nl.len_utf16()))
+    })
+}
+
+/// Returns the start & end indices of the last UTF-16-encoded newline
+/// sequence in `s` that is in `nlset`
+pub(crate) fn rsearch_utf16(nlset: NewlineSet, s: &[u16]) -> Option<(usize, usize)> {
+    if nlset.is_empty() {
+        return None;
+    }
+    (0..s.len()).rev().find_map(|i| {
+        if nlset.crlf && s[i] == 0x000A && i > 0 && s[i - 1] == 0x000D {
+            return Some((i - 1, i + 1));
+        }
+        let nl = newline_prefix_utf16(nlset, &s[i..])?;
+        Some((i, i + nl.len_utf16()))
+    })
+}
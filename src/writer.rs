@@ -0,0 +1,219 @@
+use crate::bytes::Converter;
+use crate::nl::Newline;
+use crate::nlset::NewlineSet;
+use std::io::{self, Write};
+
+/// A writer adapter that converts newline sequences as data is written.
+///
+/// A `NewlineWriter` wraps another [`Write`] value and replaces every
+/// UTF-8-encoded newline sequence in a source [`NewlineSet`] with a target
+/// [`Newline`] before passing the data on.  The data written need not be
+/// valid UTF-8.
+///
+/// Newline sequences may be split across multiple calls to `write()`: if a
+/// write ends with a CR (when the source set contains [`Newline::CrLf`]) or
+/// with an incomplete UTF-8 encoding of a newline sequence in the source set,
+/// those bytes are held back until the next write shows how they should be
+/// converted.  Calling [`flush()`][Write::flush] does not release held-back
+/// bytes, as doing so could split a CR LF pair; they are only written out
+/// when the `NewlineWriter` is [finished][NewlineWriter::finish] or dropped,
+/// at which point they are converted as though no more data will follow.
+///
+/// Converted data is written to the inner writer as soon as possible.  If the
+/// inner writer returns an error after a call to `write()` has accepted its
+/// input, the error is reported by the next call to `write()`, `flush()`, or
+/// [`finish()`][NewlineWriter::finish].  Errors that occur when a
+/// `NewlineWriter` is dropped are ignored; call `finish()` to handle them.
+///
+/// # Example
+///
+/// ```
+/// use newlines::{Newline, NewlineSet, NewlineWriter};
+/// use std::io::Write;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut writer = NewlineWriter::new(Vec::new(), NewlineSet::ASCII, Newline::LineFeed);
+/// writer.write_all(b"foo\r")?;
+/// writer.write_all(b"\nbar\r")?;
+/// let buf = writer.finish()?;
+/// assert_eq!(buf, b"foo\nbar\n");
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+pub struct NewlineWriter<W: Write> {
+    /// The inner writer.  This is only `None` after `finish()` is called.
+    inner: Option<W>,
+    converter: Converter,
+
+    /// Converted data that has not yet been written to `inner`
+    out: Vec<u8>,
+}
+
+impl<W: Write> NewlineWriter<W> {
+    /// Create a new `NewlineWriter` that converts the newline sequences in
+    /// `from` to `to` and writes the results to `inner`
+    pub fn new(inner: W, from: NewlineSet, to: Newline) -> NewlineWriter<W> {
+        NewlineWriter {
+            inner: Some(inner),
+            converter: Converter::new(from, to),
+            out: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the inner writer
+    pub fn get_ref(&self) -> &W {
+        let Some(inner) = self.inner.as_ref() else {
+            unreachable!("NewlineWriter.inner should only be None after finish()");
+        };
+        inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Writing directly to the inner writer may cause data to be output out of
+    /// order.
+    pub fn get_mut(&mut self) -> &mut W {
+        let Some(inner) = self.inner.as_mut() else {
+            unreachable!("NewlineWriter.inner should only be None after finish()");
+        };
+        inner
+    }
+
+    /// Convert & write out any held-back bytes as though no more data will
+    /// be written, write out all converted data, and return the inner writer.
+    ///
+    /// The inner writer is not flushed.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.finish_output()?;
+        let Some(inner) = self.inner.take() else {
+            unreachable!("NewlineWriter.inner should only be None after finish()");
+        };
+        Ok(inner)
+    }
+
+    /// Write as much of `out` as possible to the inner writer
+    fn flush_buf(&mut self) -> io::Result<()> {
+        let Some(inner) = self.inner.as_mut() else {
+            unreachable!("NewlineWriter.inner should only be None after finish()");
+        };
+        let mut written = 0;
+        let r = loop {
+            if written >= self.out.len() {
+                break Ok(());
+            }
+            match inner.write(&self.out[written..]) {
+                Ok(0) => {
+                    break Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the converted data",
+                    ))
+                }
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => break Err(e),
+            }
+        };
+        self.out.drain(..written);
+        r
+    }
+
+    fn finish_output(&mut self) -> io::Result<()> {
+        self.converter.convert(&[], true, &mut self.out);
+        self.flush_buf()
+    }
+}
+
+impl<W: Write> Write for NewlineWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.flush_buf()?;
+        self.converter.convert(buf, false, &mut self.out);
+        // The input has now been accepted, so any error that occurs while
+        // writing it out will be reported by the next method call.
+        let _ = self.flush_buf();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for NewlineWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.finish_output();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::normalize;
+
+    fn check_all_splits(s: &str, from: NewlineSet, to: Newline) {
+        let expected = normalize(s, from, to);
+        for i in 0..=s.len() {
+            let mut writer = NewlineWriter::new(Vec::new(), from, to);
+            writer.write_all(&s.as_bytes()[..i]).unwrap();
+            writer.write_all(&s.as_bytes()[i..]).unwrap();
+            let buf = writer.finish().unwrap();
+            assert_eq!(buf, expected.as_bytes(), "s={s:?}, split at {i}");
+        }
+        let mut buf = Vec::new();
+        {
+            let mut writer = NewlineWriter::new(&mut buf, from, to);
+            for b in s.bytes() {
+                writer.write_all(&[b]).unwrap();
+            }
+        }
+        assert_eq!(buf, expected.as_bytes(), "s={s:?}, bytewise");
+    }
+
+    #[test]
+    fn split_writes() {
+        let s = "foo\r\nbar\rbaz\n\u{2028}\u{0085}\u{2029}\r\r\n\n\r";
+        for from in [NewlineSet::RUST, NewlineSet::ASCII, NewlineSet::UNICODE] {
+            for to in Newline::iter() {
+                check_all_splits(s, from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn flush_holds_back_cr() {
+        let mut writer = NewlineWriter::new(Vec::new(), NewlineSet::ASCII, Newline::LineFeed);
+        writer.write_all(b"foo\r").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref(), b"foo");
+        writer.write_all(b"\nbar").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref(), b"foo\nbar");
+        assert_eq!(writer.finish().unwrap(), b"foo\nbar");
+    }
+
+    #[test]
+    fn cr_without_crlf_not_held() {
+        let mut writer = NewlineWriter::new(
+            Vec::new(),
+            Newline::CarriageReturn.into(),
+            Newline::LineFeed,
+        );
+        writer.write_all(b"foo\r").unwrap();
+        assert_eq!(writer.get_ref(), b"foo\n");
+        writer.write_all(b"\n").unwrap();
+        assert_eq!(writer.finish().unwrap(), b"foo\n\n");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut writer = NewlineWriter::new(Vec::new(), NewlineSet::UNICODE, Newline::CrLf);
+        writer.write_all(b"\xFF\xE2\x80").unwrap();
+        writer.write_all(b"\xA8\xE2").unwrap();
+        writer.write_all(b"\xC2").unwrap();
+        assert_eq!(writer.finish().unwrap(), b"\xFF\r\n\xE2\xC2");
+    }
+}